@@ -0,0 +1,170 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use dlt_convert::{
+    message_offsets, messages, strip_null, write_message, ExtendedHeader, Id4, LogTypeInfo,
+    Message, MessageInfo, StandardExtensions, StandardHeader, StorageHeader, StorageTime,
+};
+use std::hint::black_box;
+
+const MESSAGES: usize = 4000;
+
+/// A capture alternating between verbose messages with a string and an
+/// integer argument and non-verbose messages with a message ID and 8 bytes of
+/// data.
+fn capture() -> Vec<u8> {
+    let mut data = Vec::new();
+
+    for i in 0..MESSAGES {
+        let verbose = i % 2 == 0;
+
+        let mut payload = Vec::new();
+        if verbose {
+            let text = format!("value {i}\0");
+            payload.extend_from_slice(&(0x200u32 | 1 << 15).to_le_bytes());
+            payload.extend_from_slice(&(text.len() as u16).to_le_bytes());
+            payload.extend_from_slice(text.as_bytes());
+            payload.extend_from_slice(&0x43u32.to_le_bytes());
+            payload.extend_from_slice(&(i as u32).to_le_bytes());
+        } else {
+            payload.extend_from_slice(&(i as u32).to_le_bytes());
+            payload.extend_from_slice(&[0xab; 8]);
+        }
+
+        let message = Message {
+            storage_header: Some(StorageHeader {
+                pattern: *b"DLT\x01",
+                timestamp: StorageTime {
+                    secs: 1_700_000_000 + i as u32,
+                    micros: 0,
+                },
+                ecu: Id4::new(*b"ECU1"),
+            }),
+            standard_header: StandardHeader {
+                htyp: 0x20,
+                mcnt: i as u8,
+                len: 0,
+            },
+            extensions: StandardExtensions {
+                ecu_id: Some(Id4::new(*b"ECU1")),
+                session_id: None,
+                timestamp: Some(i as u32 * 10),
+            },
+            extended_header: Some(ExtendedHeader {
+                message_type: MessageInfo::Log {
+                    level: LogTypeInfo::Info,
+                },
+                verbose,
+                noar: if verbose { 2 } else { 0 },
+                apid: Id4::new(*b"APP\0"),
+                ctid: Id4::new(*b"CTX\0"),
+            }),
+            payload: &payload,
+            msb_first: None,
+        };
+
+        write_message(&message, &mut data);
+    }
+
+    data
+}
+
+fn parse(c: &mut Criterion) {
+    let data = capture();
+
+    for (name, throughput) in [
+        ("bytes", Throughput::Bytes(data.len() as u64)),
+        ("messages", Throughput::Elements(MESSAGES as u64)),
+    ] {
+        let mut group = c.benchmark_group(name);
+        group.throughput(throughput);
+
+        group.bench_function("headers", |b| {
+            b.iter(|| messages(black_box(&data)).filter(Result::is_ok).count())
+        });
+
+        // Selecting by application ID reads no other ID, the storage header
+        // ECU is never converted to text.
+        group.bench_function("filter_app", |b| {
+            b.iter(|| {
+                messages(black_box(&data))
+                    .filter_map(Result::ok)
+                    .filter(|message| {
+                        message
+                            .extended_header
+                            .as_ref()
+                            .is_some_and(|it| it.apid == *"APP")
+                    })
+                    .count()
+            })
+        });
+
+        group.bench_function("verbose_payload", |b| {
+            b.iter(|| {
+                messages(black_box(&data))
+                    .filter_map(Result::ok)
+                    .filter_map(|message| {
+                        Some(message.argument_iter()?.filter(Result::is_ok).count())
+                    })
+                    .sum::<usize>()
+            })
+        });
+
+        group.finish();
+    }
+}
+
+/// Turning every payload into text, the way the CLI used to with an
+/// allocation per message, against writing it into one reused buffer. The
+/// latter also decodes the verbose arguments instead of copying their raw
+/// bytes, so it does more work per message.
+fn payload_text(c: &mut Criterion) {
+    let data = capture();
+
+    let mut group = c.benchmark_group("payload_text");
+    group.throughput(Throughput::Elements(MESSAGES as u64));
+
+    group.bench_function("from_utf8_lossy", |b| {
+        b.iter(|| {
+            messages(black_box(&data))
+                .filter_map(Result::ok)
+                .map(|message| String::from_utf8_lossy(strip_null(message.payload)).len())
+                .sum::<usize>()
+        })
+    });
+
+    group.bench_function("write_payload_text", |b| {
+        let mut text = String::new();
+        b.iter(|| {
+            messages(black_box(&data))
+                .filter_map(Result::ok)
+                .map(|message| {
+                    text.clear();
+                    message.write_payload_text(&mut text).unwrap();
+                    text.len()
+                })
+                .sum::<usize>()
+        })
+    });
+
+    group.finish();
+}
+
+/// Resynchronizing through a capture whose messages are separated by
+/// garbage. Compare runs with and without the `memchr` feature to measure the
+/// accelerated magic search.
+fn resync(c: &mut Criterion) {
+    let mut data = Vec::new();
+    for message in capture().chunks(1000) {
+        data.extend_from_slice(message);
+        data.extend_from_slice(&[0x55; 64 * 1024]);
+    }
+
+    let mut group = c.benchmark_group("resync");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    group.bench_function("message_offsets", |b| {
+        b.iter(|| message_offsets(black_box(&data)).len())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse, payload_text, resync);
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+//! Converts a `.dlt` file to CSV.
+//!
+//! ```sh
+//! cargo run --example dlt_to_csv --features csv -- input.dlt output.csv
+//! ```
+
+use dlt_convert::{messages, write_csv};
+use std::{env, fs::File, io::BufWriter, process};
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let (Some(input), Some(output)) = (args.next(), args.next()) else {
+        eprintln!("usage: dlt_to_csv <input.dlt> <output.csv>");
+        process::exit(1);
+    };
+
+    let data = match std::fs::read(&input) {
+        Ok(it) => it,
+        Err(err) => {
+            eprintln!("error: {input}: {err}");
+            process::exit(1);
+        }
+    };
+
+    let out = match File::create(&output) {
+        Ok(it) => BufWriter::new(it),
+        Err(err) => {
+            eprintln!("error: {output}: {err}");
+            process::exit(1);
+        }
+    };
+
+    // Messages are converted up to the first parse error.
+    let messages = messages(&data).map_while(Result::ok);
+
+    if let Err(err) = write_csv(messages, out) {
+        eprintln!("error: {output}: {err}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Malformed input must only ever produce errors, never panics.
+fuzz_target!(|data: &[u8]| {
+    let _ = dlt_convert::parse_message(data);
+    let _ = dlt_convert::parse_message_no_storage(data);
+
+    dlt_convert::messages(data).with_resync(true).count();
+});
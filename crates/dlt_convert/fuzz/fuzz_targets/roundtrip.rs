@@ -0,0 +1,25 @@
+#![no_main]
+
+use dlt_convert::{parse_message_with, write_message, ParseOptions};
+use libfuzzer_sys::fuzz_target;
+
+// Every message the parser accepts must encode back to exactly the bytes it
+// was parsed from, and parse again into the same message.
+fuzz_target!(|data: &[u8]| {
+    check(data, &ParseOptions::new());
+    check(data, &ParseOptions::new().storage_header(false));
+});
+
+fn check(data: &[u8], opts: &ParseOptions) {
+    let Ok((message, rest)) = parse_message_with(data, opts) else {
+        return;
+    };
+
+    let mut encoded = Vec::new();
+    write_message(&message, &mut encoded);
+    assert_eq!(encoded, data[..data.len() - rest.len()]);
+
+    let (reparsed, rest) = parse_message_with(&encoded, opts).expect("encoded message parses");
+    assert!(rest.is_empty());
+    assert_eq!(format!("{reparsed:?}"), format!("{message:?}"));
+}
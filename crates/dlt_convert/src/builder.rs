@@ -0,0 +1,186 @@
+use crate::{
+    verbose::{BOOL, FLOA, RAWD, SCOD_SHIFT, SCOD_UTF8, SINT, STRG, UINT},
+    ExtendedHeader, HeaderType, Id4, LogTypeInfo, MessageInfo, OwnedMessage, StandardExtensions,
+    StandardHeader, StorageHeader, StorageTime, STORAGE_MAGIC,
+};
+use alloc::vec::Vec;
+
+/// Builds verbose log messages, mainly for tests.
+///
+/// The message gets a storage header and an ECU ID extension, both with the
+/// ECU set by [`MessageBuilder::ecu`], and is encoded little-endian. The
+/// storage timestamp and the counter default to zero.
+///
+/// The ID setters panic if the ID is longer than 4 bytes or not ASCII.
+#[derive(Debug, Clone)]
+pub struct MessageBuilder {
+    level: LogTypeInfo,
+    ecu: Id4,
+    apid: Id4,
+    ctid: Id4,
+    timestamp: StorageTime,
+    mcnt: u8,
+    session_id: Option<u32>,
+    noar: u8,
+    payload: Vec<u8>,
+}
+
+impl MessageBuilder {
+    pub fn log(level: LogTypeInfo) -> Self {
+        Self {
+            level,
+            ecu: Id4::default(),
+            apid: Id4::default(),
+            ctid: Id4::default(),
+            timestamp: StorageTime { secs: 0, micros: 0 },
+            mcnt: 0,
+            session_id: None,
+            noar: 0,
+            payload: Vec::new(),
+        }
+    }
+
+    pub fn ecu(mut self, ecu: &str) -> Self {
+        self.ecu = parse_id(ecu);
+        self
+    }
+
+    pub fn app(mut self, apid: &str) -> Self {
+        self.apid = parse_id(apid);
+        self
+    }
+
+    pub fn ctx(mut self, ctid: &str) -> Self {
+        self.ctid = parse_id(ctid);
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: StorageTime) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn counter(mut self, mcnt: u8) -> Self {
+        self.mcnt = mcnt;
+        self
+    }
+
+    /// Adds the session ID extension to the standard header.
+    pub fn session_id(mut self, session_id: u32) -> Self {
+        self.session_id = Some(session_id);
+        self
+    }
+
+    pub fn arg_bool(self, value: bool) -> Self {
+        self.arg(BOOL | 0x1, &[u8::from(value)])
+    }
+
+    pub fn arg_i32(self, value: i32) -> Self {
+        self.arg(SINT | 0x3, &value.to_le_bytes())
+    }
+
+    pub fn arg_i64(self, value: i64) -> Self {
+        self.arg(SINT | 0x4, &value.to_le_bytes())
+    }
+
+    pub fn arg_u32(self, value: u32) -> Self {
+        self.arg(UINT | 0x3, &value.to_le_bytes())
+    }
+
+    pub fn arg_u64(self, value: u64) -> Self {
+        self.arg(UINT | 0x4, &value.to_le_bytes())
+    }
+
+    pub fn arg_f64(self, value: f64) -> Self {
+        self.arg(FLOA | 0x4, &value.to_le_bytes())
+    }
+
+    /// Adds a UTF-8 string argument with a null terminator.
+    pub fn arg_str(self, value: &str) -> Self {
+        let mut data = length_prefix(value.len() + 1);
+        data.extend_from_slice(value.as_bytes());
+        data.push(0);
+
+        self.arg(STRG | SCOD_UTF8 << SCOD_SHIFT, &data)
+    }
+
+    pub fn arg_raw(self, value: &[u8]) -> Self {
+        let mut data = length_prefix(value.len());
+        data.extend_from_slice(value);
+
+        self.arg(RAWD, &data)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the message does not fit into the 16-bit length field.
+    pub fn build(self) -> OwnedMessage {
+        let htyp = HeaderType {
+            use_extended_header: true,
+            msb_first: false,
+            with_ecu_id: true,
+            with_session_id: self.session_id.is_some(),
+            with_timestamp: false,
+            version: 1,
+        }
+        .to_raw();
+
+        // Standard header, ECU ID, session ID and extended header.
+        let session_id_len = if self.session_id.is_some() { 4 } else { 0 };
+        let len = 4 + 4 + session_id_len + 10 + self.payload.len();
+        assert!(
+            len <= usize::from(u16::MAX),
+            "message too long for the DLT length field"
+        );
+
+        OwnedMessage {
+            storage_header: Some(StorageHeader {
+                pattern: STORAGE_MAGIC,
+                timestamp: self.timestamp,
+                ecu: self.ecu,
+            }),
+            standard_header: StandardHeader {
+                htyp,
+                mcnt: self.mcnt,
+                len,
+            },
+            extensions: StandardExtensions {
+                ecu_id: Some(self.ecu),
+                session_id: self.session_id,
+                timestamp: None,
+            },
+            extended_header: Some(ExtendedHeader {
+                message_type: MessageInfo::Log { level: self.level },
+                verbose: true,
+                noar: self.noar,
+                apid: self.apid,
+                ctid: self.ctid,
+            }),
+            payload: self.payload,
+            msb_first: None,
+        }
+    }
+
+    fn arg(mut self, type_info: u32, data: &[u8]) -> Self {
+        self.noar = self
+            .noar
+            .checked_add(1)
+            .expect("a message has at most 255 arguments");
+        self.payload.extend_from_slice(&type_info.to_le_bytes());
+        self.payload.extend_from_slice(data);
+        self
+    }
+}
+
+pub(crate) fn parse_id(id: &str) -> Id4 {
+    id.parse()
+        .unwrap_or_else(|_| panic!("invalid ID {id:?}, IDs are at most 4 ASCII characters"))
+}
+
+/// # Panics
+///
+/// Panics if `len` does not fit into the 16-bit length of an argument.
+fn length_prefix(len: usize) -> Vec<u8> {
+    let len = u16::try_from(len).expect("argument too long for its 16-bit length");
+    len.to_le_bytes().to_vec()
+}
@@ -0,0 +1,84 @@
+use crate::Id4;
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+/// Looks up the definitions of non-verbose messages.
+///
+/// See [`Message::format_non_verbose`](crate::Message::format_non_verbose).
+pub trait MessageCatalog {
+    fn lookup(&self, apid: Id4, ctid: Id4, id: u32) -> Option<&MessageDef>;
+}
+
+/// Definition of a non-verbose message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageDef {
+    pub apid: Id4,
+    pub ctid: Id4,
+    pub id: u32,
+    /// Text in which every `{}` is replaced by the next argument.
+    pub format: String,
+    /// Types of the arguments following the message ID, in payload order.
+    pub arguments: Vec<ArgumentType>,
+}
+
+/// Type of a non-verbose argument.
+///
+/// Strings are UTF-8 and, like raw data, prefixed with a 16-bit length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArgumentType {
+    Bool,
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
+    F64,
+    String,
+    Raw,
+}
+
+/// Catalog built from a list of definitions.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryCatalog {
+    defs: BTreeMap<u32, Vec<MessageDef>>,
+}
+
+impl MemoryCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `def`, replacing an earlier definition for the same application,
+    /// context and message ID.
+    pub fn insert(&mut self, def: MessageDef) {
+        let defs = self.defs.entry(def.id).or_default();
+
+        match defs
+            .iter_mut()
+            .find(|it| it.apid == def.apid && it.ctid == def.ctid)
+        {
+            Some(existing) => *existing = def,
+            None => defs.push(def),
+        }
+    }
+}
+
+impl FromIterator<MessageDef> for MemoryCatalog {
+    fn from_iter<T: IntoIterator<Item = MessageDef>>(iter: T) -> Self {
+        let mut catalog = Self::new();
+        iter.into_iter().for_each(|it| catalog.insert(it));
+        catalog
+    }
+}
+
+impl MessageCatalog for MemoryCatalog {
+    fn lookup(&self, apid: Id4, ctid: Id4, id: u32) -> Option<&MessageDef> {
+        self.defs
+            .get(&id)?
+            .iter()
+            .find(|it| it.apid == apid && it.ctid == ctid)
+    }
+}
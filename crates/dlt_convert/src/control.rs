@@ -0,0 +1,385 @@
+use crate::{strip_null, ControlKind, Id4};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+
+#[derive(Debug)]
+pub struct ControlMessage<'a> {
+    /// The kind from the extended header, or `None` if only the payload was
+    /// parsed with [`parse_control_payload`].
+    pub kind: Option<ControlKind>,
+    pub service_id: ServiceId,
+    /// Everything after the service ID. For responses this starts with the
+    /// status byte.
+    pub data: &'a [u8],
+}
+
+impl ControlMessage<'_> {
+    /// Reads the leading status byte, or returns `None` if this is not known
+    /// to be a response.
+    pub fn status(&self) -> Option<ResponseStatus> {
+        if self.kind != Some(ControlKind::Response) {
+            return None;
+        }
+
+        self.data.first().map(|&it| ResponseStatus::from_raw(it))
+    }
+}
+
+pub fn parse_control_payload(payload: &[u8], msb_first: bool) -> Option<ControlMessage<'_>> {
+    let (service_id_bytes, data) = payload.split_first_chunk::<4>()?;
+
+    let service_id = if msb_first {
+        u32::from_be_bytes(*service_id_bytes)
+    } else {
+        u32::from_le_bytes(*service_id_bytes)
+    };
+
+    Some(ControlMessage {
+        kind: None,
+        service_id: ServiceId::from_raw(service_id),
+        data,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceId {
+    SetLogLevel,
+    SetTraceStatus,
+    GetLogInfo,
+    GetDefaultLogLevel,
+    StoreConfiguration,
+    ResetToFactoryDefault,
+    SetMessageFiltering,
+    SetDefaultLogLevel,
+    SetDefaultTraceStatus,
+    GetSoftwareVersion,
+    GetDefaultTraceStatus,
+    GetLogChannelNames,
+    GetTraceStatus,
+    SetLogChannelAssignment,
+    SetLogChannelThreshold,
+    GetLogChannelThreshold,
+    BufferOverflowNotification,
+    Unknown(u32),
+}
+
+impl ServiceId {
+    fn from_raw(id: u32) -> Self {
+        match id {
+            0x01 => Self::SetLogLevel,
+            0x02 => Self::SetTraceStatus,
+            0x03 => Self::GetLogInfo,
+            0x04 => Self::GetDefaultLogLevel,
+            0x05 => Self::StoreConfiguration,
+            0x06 => Self::ResetToFactoryDefault,
+            0x0A => Self::SetMessageFiltering,
+            0x11 => Self::SetDefaultLogLevel,
+            0x12 => Self::SetDefaultTraceStatus,
+            0x13 => Self::GetSoftwareVersion,
+            0x15 => Self::GetDefaultTraceStatus,
+            0x17 => Self::GetLogChannelNames,
+            0x1F => Self::GetTraceStatus,
+            0x20 => Self::SetLogChannelAssignment,
+            0x21 => Self::SetLogChannelThreshold,
+            0x22 => Self::GetLogChannelThreshold,
+            0x23 => Self::BufferOverflowNotification,
+            _ => Self::Unknown(id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseStatus {
+    Ok,
+    NotSupported,
+    Error,
+    Other(u8),
+}
+
+impl ResponseStatus {
+    fn from_raw(status: u8) -> Self {
+        match status {
+            0x0 => Self::Ok,
+            0x1 => Self::NotSupported,
+            0x2 => Self::Error,
+            _ => Self::Other(status),
+        }
+    }
+}
+
+/// Decoded payload of a `GetLogInfo` response, listing the applications and
+/// contexts registered at the ECU.
+#[derive(Debug)]
+pub struct GetLogInfoResponse<'a> {
+    /// The status byte. `3` to `7` select which fields are present: `4` and
+    /// `6` add log levels, `5` and `6` trace statuses and `7` all of them
+    /// plus descriptions. Any other status carries no applications.
+    pub status: u8,
+    pub apps: Vec<AppLogInfo<'a>>,
+}
+
+#[derive(Debug)]
+pub struct AppLogInfo<'a> {
+    pub apid: Id4,
+    pub contexts: Vec<ContextLogInfo<'a>>,
+    pub description: Option<Cow<'a, str>>,
+}
+
+/// Log level and trace status are the raw values, with `-1` meaning the
+/// default of the ECU.
+#[derive(Debug)]
+pub struct ContextLogInfo<'a> {
+    pub ctid: Id4,
+    pub log_level: Option<i8>,
+    pub trace_status: Option<i8>,
+    pub description: Option<Cow<'a, str>>,
+}
+
+/// Decodes the control payload of a `GetLogInfo` response, or returns `None`
+/// if it is not one or ends early.
+pub fn parse_get_log_info(payload: &[u8], msb_first: bool) -> Option<GetLogInfoResponse<'_>> {
+    let message = parse_control_payload(payload, msb_first)?;
+    if message.service_id != ServiceId::GetLogInfo {
+        return None;
+    }
+
+    let (&status, mut data) = message.data.split_first()?;
+    let mut apps = Vec::new();
+
+    if !(3..=7).contains(&status) {
+        return Some(GetLogInfoResponse { status, apps });
+    }
+
+    let with_log_level = matches!(status, 4 | 6 | 7);
+    let with_trace_status = matches!(status, 5..=7);
+    let with_description = status == 7;
+
+    for _ in 0..read_u16(&mut data, msb_first)? {
+        let apid = Id4::new(*take::<4>(&mut data)?);
+        let mut contexts = Vec::new();
+
+        for _ in 0..read_u16(&mut data, msb_first)? {
+            let ctid = Id4::new(*take::<4>(&mut data)?);
+            let log_level = if with_log_level {
+                Some(take::<1>(&mut data)?[0] as i8)
+            } else {
+                None
+            };
+            let trace_status = if with_trace_status {
+                Some(take::<1>(&mut data)?[0] as i8)
+            } else {
+                None
+            };
+            let description = if with_description {
+                Some(read_description(&mut data, msb_first)?)
+            } else {
+                None
+            };
+
+            contexts.push(ContextLogInfo {
+                ctid,
+                log_level,
+                trace_status,
+                description,
+            });
+        }
+
+        let description = if with_description {
+            Some(read_description(&mut data, msb_first)?)
+        } else {
+            None
+        };
+
+        apps.push(AppLogInfo {
+            apid,
+            contexts,
+            description,
+        });
+    }
+
+    Some(GetLogInfoResponse { status, apps })
+}
+
+fn take<'a, const N: usize>(data: &mut &'a [u8]) -> Option<&'a [u8; N]> {
+    let (bytes, rest) = data.split_first_chunk::<N>()?;
+    *data = rest;
+    Some(bytes)
+}
+
+fn read_u16(data: &mut &[u8], msb_first: bool) -> Option<u16> {
+    let bytes = take::<2>(data)?;
+    Some(if msb_first {
+        u16::from_be_bytes(*bytes)
+    } else {
+        u16::from_le_bytes(*bytes)
+    })
+}
+
+fn read_description<'a>(data: &mut &'a [u8], msb_first: bool) -> Option<Cow<'a, str>> {
+    let len = read_u16(data, msb_first)?;
+    let (text, rest) = data.split_at_checked(len as usize)?;
+    *data = rest;
+    Some(String::from_utf8_lossy(strip_null(text)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse_message_no_storage, write_message, ExtendedHeader, Message, MessageInfo,
+        StandardExtensions, StandardHeader,
+    };
+    use alloc::vec;
+
+    /// A control message of `kind` as sent between the viewer and the ECU.
+    fn control(kind: ControlKind, payload: &[u8]) -> Vec<u8> {
+        let message = Message {
+            storage_header: None,
+            standard_header: StandardHeader {
+                htyp: 0x20,
+                mcnt: 0,
+                len: 0,
+            },
+            extensions: StandardExtensions {
+                ecu_id: Some(Id4::new(*b"ECU1")),
+                session_id: None,
+                timestamp: None,
+            },
+            extended_header: Some(ExtendedHeader {
+                message_type: MessageInfo::Control { kind },
+                verbose: false,
+                noar: 0,
+                apid: Id4::new(*b"DA1\0"),
+                ctid: Id4::new(*b"DC1\0"),
+            }),
+            payload,
+            msb_first: None,
+        };
+        let mut data = Vec::new();
+        write_message(&message, &mut data);
+        data
+    }
+
+    fn kind(message: &Message) -> ControlKind {
+        match message.extended_header.as_ref().unwrap().message_type {
+            MessageInfo::Control { kind } => kind,
+            ref other => panic!("not a control message: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn get_log_info_request_and_response() {
+        // All applications and contexts, with log levels, trace statuses
+        // and descriptions.
+        let mut request = Vec::new();
+        request.extend_from_slice(&3u32.to_le_bytes());
+        request.push(7);
+        request.extend_from_slice(&[0; 8]);
+        request.extend_from_slice(b"remo");
+        let request = control(ControlKind::Request, &request);
+
+        // No matching contexts.
+        let mut response = Vec::new();
+        response.extend_from_slice(&3u32.to_le_bytes());
+        response.push(8);
+        response.extend_from_slice(b"remo");
+        let response = control(ControlKind::Response, &response);
+
+        let (request, _) = parse_message_no_storage(&request).unwrap();
+        let (response, _) = parse_message_no_storage(&response).unwrap();
+
+        assert_eq!(kind(&request), ControlKind::Request);
+        assert_eq!(kind(&response), ControlKind::Response);
+
+        let control = request.control_message().unwrap();
+        assert_eq!(control.service_id, ServiceId::GetLogInfo);
+        // The first byte of a request is not a status.
+        assert_eq!(control.status(), None);
+
+        let control = response.control_message().unwrap();
+        assert_eq!(control.kind, Some(ControlKind::Response));
+        assert_eq!(control.service_id, ServiceId::GetLogInfo);
+        assert_eq!(control.status(), Some(ResponseStatus::Other(8)));
+
+        // Without the extended header the kind is unknown.
+        let control = parse_control_payload(response.payload, false).unwrap();
+        assert_eq!(control.service_id, ServiceId::GetLogInfo);
+        assert_eq!(control.status(), None);
+
+        let info = parse_get_log_info(response.payload, false).unwrap();
+        assert_eq!(info.status, 8);
+        assert!(info.apps.is_empty());
+    }
+
+    #[test]
+    fn get_log_info_response_with_two_apps() {
+        // Status 7: log levels, trace statuses and descriptions.
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&3u32.to_le_bytes());
+        payload.push(7);
+        payload.extend_from_slice(&2u16.to_le_bytes());
+
+        payload.extend_from_slice(b"APP1");
+        payload.extend_from_slice(&2u16.to_le_bytes());
+        payload.extend_from_slice(b"CTX1\x04\x01");
+        payload.extend_from_slice(&4u16.to_le_bytes());
+        payload.extend_from_slice(b"Main");
+        payload.extend_from_slice(b"CTX2\xff\xff");
+        payload.extend_from_slice(&0u16.to_le_bytes());
+        payload.extend_from_slice(&8u16.to_le_bytes());
+        payload.extend_from_slice(b"App one\0");
+
+        payload.extend_from_slice(b"APP2");
+        payload.extend_from_slice(&1u16.to_le_bytes());
+        payload.extend_from_slice(b"CTX3\x06\x00");
+        payload.extend_from_slice(&0u16.to_le_bytes());
+        payload.extend_from_slice(&0u16.to_le_bytes());
+
+        payload.extend_from_slice(b"remo");
+
+        let info = parse_get_log_info(&payload, false).unwrap();
+        assert_eq!(info.status, 7);
+
+        let apps: Vec<_> = info
+            .apps
+            .iter()
+            .map(|app| {
+                let contexts: Vec<_> = app
+                    .contexts
+                    .iter()
+                    .map(|it| {
+                        (
+                            it.ctid,
+                            it.log_level,
+                            it.trace_status,
+                            it.description.as_deref(),
+                        )
+                    })
+                    .collect();
+                (app.apid, app.description.as_deref(), contexts)
+            })
+            .collect();
+        let id = |it: &[u8; 4]| Id4::new(*it);
+
+        assert_eq!(
+            apps,
+            [
+                (
+                    id(b"APP1"),
+                    Some("App one"),
+                    vec![
+                        (id(b"CTX1"), Some(4), Some(1), Some("Main")),
+                        (id(b"CTX2"), Some(-1), Some(-1), Some("")),
+                    ]
+                ),
+                (
+                    id(b"APP2"),
+                    Some(""),
+                    vec![(id(b"CTX3"), Some(6), Some(0), Some(""))]
+                ),
+            ]
+        );
+
+        // Cut inside the second application.
+        assert!(parse_get_log_info(&payload[..40], false).is_none());
+    }
+}
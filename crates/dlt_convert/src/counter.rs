@@ -0,0 +1,63 @@
+/// Detects gaps in the 8-bit message counter of a single stream.
+///
+/// Counters are maintained per sender, so keep one tracker per ECU or
+/// application when a capture multiplexes several of them.
+#[derive(Debug, Clone, Default)]
+pub struct CounterTracker {
+    last: Option<u8>,
+}
+
+impl CounterTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the next counter value and returns the number of messages
+    /// missed since the previous one, if there was a gap.
+    ///
+    /// A value equal to the previous one is taken to be a duplicate of the
+    /// same message, as captured twice, and is not reported, although it
+    /// could also mean exactly 255 messages were missed.
+    pub fn observe(&mut self, mcnt: u8) -> Option<u8> {
+        let last = self.last.replace(mcnt)?;
+        if mcnt == last {
+            return None;
+        }
+
+        let missed = mcnt.wrapping_sub(last).wrapping_sub(1);
+
+        (missed != 0).then_some(missed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn observe_all(counters: &[u8]) -> Vec<Option<u8>> {
+        let mut tracker = CounterTracker::new();
+        counters.iter().map(|&it| tracker.observe(it)).collect()
+    }
+
+    #[test]
+    fn consecutive_counters_have_no_gap() {
+        assert_eq!(observe_all(&[3, 4, 5, 6]), [None; 4]);
+    }
+
+    #[test]
+    fn wrapping_from_255_to_0_is_no_gap() {
+        assert_eq!(observe_all(&[254, 255, 0, 1]), [None; 4]);
+        assert_eq!(observe_all(&[254, 1]), [None, Some(2)]);
+    }
+
+    #[test]
+    fn gaps_report_the_missed_messages() {
+        assert_eq!(observe_all(&[10, 11, 15, 16]), [None, None, Some(3), None]);
+    }
+
+    #[test]
+    fn repeated_counters_are_duplicates() {
+        assert_eq!(observe_all(&[1, 1, 2]), [None, None, None]);
+    }
+}
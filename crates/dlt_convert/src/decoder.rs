@@ -0,0 +1,19 @@
+use crate::Message;
+use alloc::string::String;
+
+/// Turns payloads of a custom format into text, see
+/// [`Message::format_line_with`](crate::Message::format_line_with).
+///
+/// Implemented for closures taking a `&Message`, for decoders that do not
+/// need a type of their own.
+pub trait PayloadDecoder {
+    /// The payload as text, or `None` if this decoder does not handle the
+    /// message.
+    fn decode(&self, msg: &Message) -> Option<String>;
+}
+
+impl<F: Fn(&Message) -> Option<String>> PayloadDecoder for F {
+    fn decode(&self, msg: &Message) -> Option<String> {
+        self(msg)
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{Id4, Message};
+use core::iter::FusedIterator;
+
+/// Collapses runs of repeated messages into their first message and the
+/// number of times it occurred in a row, like syslog's "last message repeated
+/// N times".
+///
+/// Messages repeat each other if they come from the same ECU, application and
+/// context, have the same type, level and mode and carry identical payloads.
+/// Message counters and timestamps are ignored.
+pub fn dedup_consecutive<'a, I>(messages: I) -> DedupMessages<'a, I::IntoIter>
+where
+    I: IntoIterator<Item = Message<'a>>,
+{
+    DedupMessages {
+        messages: messages.into_iter(),
+        next: None,
+    }
+}
+
+#[derive(Debug)]
+pub struct DedupMessages<'a, I> {
+    messages: I,
+    /// The first message of the next run, read while finding the end of the
+    /// current one.
+    next: Option<Message<'a>>,
+}
+
+impl<'a, I: Iterator<Item = Message<'a>>> Iterator for DedupMessages<'a, I> {
+    type Item = (Message<'a>, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.next.take().or_else(|| self.messages.next())?;
+        let mut count: u32 = 1;
+
+        for message in self.messages.by_ref() {
+            if !is_repeat(&first, &message) {
+                self.next = Some(message);
+                break;
+            }
+
+            count = count.saturating_add(1);
+        }
+
+        Some((first, count))
+    }
+}
+
+impl<'a, I: FusedIterator<Item = Message<'a>>> FusedIterator for DedupMessages<'a, I> {}
+
+fn is_repeat(a: &Message, b: &Message) -> bool {
+    let same_header = match (&a.extended_header, &b.extended_header) {
+        (Some(a), Some(b)) => {
+            a.raw_type() == b.raw_type()
+                && a.raw_subtype() == b.raw_subtype()
+                && a.verbose == b.verbose
+                && a.noar == b.noar
+                && a.apid == b.apid
+                && a.ctid == b.ctid
+        }
+        (None, None) => true,
+        _ => false,
+    };
+
+    same_header && a.ecu() == b.ecu() && a.payload == b.payload
+}
+
+/// Identifies a logical message across captures, see [`Message::dedup_key`].
+///
+/// Ordered by ECU, application, context, counter and payload hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DedupKey {
+    /// The ECU ID of the extensions, or else of the storage header.
+    pub ecu: Id4,
+    /// Empty for messages without an extended header, like `ctid`.
+    pub apid: Id4,
+    pub ctid: Id4,
+    pub mcnt: u8,
+    /// 64-bit FNV-1a hash of the payload, the same on every platform and
+    /// across versions of this crate.
+    pub payload_hash: u64,
+}
+
+impl Message<'_> {
+    /// A key that is equal for copies of the same message, for example to
+    /// drop duplicates when merging overlapping captures.
+    ///
+    /// The storage timestamp is ignored, as the same message may have been
+    /// stored at slightly different times by different loggers.
+    pub fn dedup_key(&self) -> DedupKey {
+        let (apid, ctid) = match &self.extended_header {
+            Some(extended_header) => (extended_header.apid, extended_header.ctid),
+            None => (Id4::default(), Id4::default()),
+        };
+
+        DedupKey {
+            ecu: self.ecu().unwrap_or_default(),
+            apid,
+            ctid,
+            mcnt: self.standard_header.mcnt,
+            payload_hash: fnv1a(self.payload),
+        }
+    }
+}
+
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{capture_bytes, info_message, messages, StorageTime};
+    use alloc::vec::Vec;
+
+    /// Messages with the given counter, storage seconds and text argument.
+    fn capture(messages: &[(u8, u32, &str)]) -> Vec<u8> {
+        capture_bytes(messages.iter().map(|&(counter, secs, text)| {
+            info_message(counter)
+                .timestamp(StorageTime { secs, micros: 0 })
+                .arg_str(text)
+        }))
+    }
+
+    #[test]
+    fn collapses_three_identical_messages() {
+        // Counters and timestamps differ, the rest is identical.
+        let data = capture(&[
+            (0, 10, "retry"),
+            (1, 11, "retry"),
+            (2, 12, "retry"),
+            (3, 13, "done"),
+        ]);
+
+        let runs: Vec<_> = dedup_consecutive(messages(&data).map(Result::unwrap))
+            .map(|(message, count)| (message.standard_header.mcnt, count))
+            .collect();
+
+        assert_eq!(runs, [(0, 3), (3, 1)]);
+    }
+
+    #[test]
+    fn different_context_is_no_repeat() {
+        let data = capture_bytes([
+            info_message(0).arg_str("retry"),
+            info_message(1).ctx("CTX2").arg_str("retry"),
+        ]);
+
+        assert_eq!(
+            dedup_consecutive(messages(&data).map(Result::unwrap)).count(),
+            2
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn dedup_key_drops_copies_from_another_buffer() {
+        use std::collections::HashSet;
+
+        // The second capture stored the shared message a second later.
+        let a = capture(&[(0, 10, "boot"), (1, 11, "ready")]);
+        let b = capture(&[(1, 12, "ready"), (2, 13, "shutdown")]);
+
+        let mut seen = HashSet::new();
+        let unique: Vec<_> = messages(&a)
+            .chain(messages(&b))
+            .map(Result::unwrap)
+            .filter(|it| seen.insert(it.dedup_key()))
+            .map(|it| it.standard_header.mcnt)
+            .collect();
+
+        assert_eq!(unique, [0, 1, 2]);
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
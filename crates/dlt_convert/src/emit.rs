@@ -0,0 +1,207 @@
+use crate::{LogTypeInfo, Message, MessageInfo};
+use core::fmt;
+
+/// Re-emits a log message as a `tracing` event with the target `dlt`.
+///
+/// `Fatal` and `Error` map to [`tracing::Level::ERROR`], `Warn`, `Info` and
+/// `Debug` to their namesakes and `Verbose` as well as reserved levels to
+/// [`tracing::Level::TRACE`]. The event carries `ecu`, `apid`, `ctid` and the
+/// original `level` as fields, with the payload text as its message.
+/// Messages that are not log messages are ignored.
+pub fn emit_tracing(message: &Message) {
+    let Some(extended_header) = &message.extended_header else {
+        return;
+    };
+    let MessageInfo::Log { level } = extended_header.message_type else {
+        return;
+    };
+
+    let ecu = message.ecu().unwrap_or_default();
+    let apid = extended_header.apid;
+    let ctid = extended_header.ctid;
+    let text = PayloadText(message);
+
+    macro_rules! emit {
+        ($level:ident) => {
+            tracing::event!(
+                target: "dlt",
+                tracing::Level::$level,
+                %ecu,
+                %apid,
+                %ctid,
+                level = level.as_str(),
+                "{text}"
+            )
+        };
+    }
+
+    match level {
+        LogTypeInfo::Fatal | LogTypeInfo::Error => emit!(ERROR),
+        LogTypeInfo::Warn => emit!(WARN),
+        LogTypeInfo::Info => emit!(INFO),
+        LogTypeInfo::Debug => emit!(DEBUG),
+        LogTypeInfo::Verbose | LogTypeInfo::Reserved(_) => emit!(TRACE),
+    }
+}
+
+/// Writes the payload text only when a subscriber actually records the event.
+struct PayloadText<'a, 'b>(&'a Message<'b>);
+
+impl fmt::Display for PayloadText<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.write_payload_text(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        capture_bytes, info_message, message_offsets, parse_message, ControlKind, MessageBuilder,
+    };
+    use alloc::{
+        collections::BTreeMap,
+        format,
+        string::{String, ToString},
+        vec::Vec,
+    };
+    use std::sync::{Arc, Mutex};
+    use tracing::{
+        field::{Field, Visit},
+        span, Event, Level, Metadata, Subscriber,
+    };
+
+    #[derive(Debug, PartialEq)]
+    struct Recorded {
+        level: Level,
+        target: String,
+        fields: BTreeMap<String, String>,
+    }
+
+    /// Records every event, with its fields formatted as text.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<Recorded>>>);
+
+    struct Fields(BTreeMap<String, String>);
+
+    impl Visit for Fields {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields(BTreeMap::new());
+            event.record(&mut fields);
+
+            self.0.lock().unwrap().push(Recorded {
+                level: *event.metadata().level(),
+                target: event.metadata().target().to_string(),
+                fields: fields.0,
+            });
+        }
+
+        fn enter(&self, _: &span::Id) {}
+
+        fn exit(&self, _: &span::Id) {}
+    }
+
+    /// The events emitted for the messages of `data`.
+    fn emit_all(data: &[u8], f: impl Fn(&mut Message)) -> Vec<Recorded> {
+        let recorder = Recorder::default();
+
+        tracing::subscriber::with_default(recorder.clone(), || {
+            for offset in message_offsets(data) {
+                let (mut message, _) = parse_message(&data[offset..]).unwrap();
+                f(&mut message);
+                emit_tracing(&message);
+            }
+        });
+
+        let recorded = recorder.0.lock().unwrap().drain(..).collect();
+        recorded
+    }
+
+    #[test]
+    fn maps_levels_target_and_fields() {
+        let data = capture_bytes([
+            info_message(0).arg_str("hello").arg_u32(7),
+            MessageBuilder::log(LogTypeInfo::Fatal)
+                .ecu("ECU2")
+                .app("APP2")
+                .ctx("CTX2")
+                .counter(1),
+            info_message(2).arg_str("not a log message"),
+            MessageBuilder::log(LogTypeInfo::Verbose).counter(3),
+        ]);
+        let recorded = emit_all(&data, |message| {
+            if message.standard_header.mcnt == 2 {
+                message.extended_header.as_mut().unwrap().message_type = MessageInfo::Control {
+                    kind: ControlKind::Request,
+                };
+            }
+        });
+
+        let fields = |entries: &[(&str, &str)]| -> BTreeMap<String, String> {
+            entries
+                .iter()
+                .map(|&(name, value)| (name.to_string(), value.to_string()))
+                .collect()
+        };
+        let expected = [
+            Recorded {
+                level: Level::INFO,
+                target: "dlt".to_string(),
+                fields: fields(&[
+                    ("ecu", "ECU1"),
+                    ("apid", "APP"),
+                    ("ctid", "CTX"),
+                    ("level", "info"),
+                    ("message", "hello 7"),
+                ]),
+            },
+            Recorded {
+                level: Level::ERROR,
+                target: "dlt".to_string(),
+                fields: fields(&[
+                    ("ecu", "ECU2"),
+                    ("apid", "APP2"),
+                    ("ctid", "CTX2"),
+                    ("level", "fatal"),
+                    ("message", ""),
+                ]),
+            },
+            Recorded {
+                level: Level::TRACE,
+                target: "dlt".to_string(),
+                fields: fields(&[
+                    ("ecu", ""),
+                    ("apid", ""),
+                    ("ctid", ""),
+                    ("level", "verbose"),
+                    ("message", ""),
+                ]),
+            },
+        ];
+
+        assert_eq!(recorded, expected);
+    }
+}
@@ -0,0 +1,332 @@
+use crate::{HeaderType, Message};
+use alloc::vec::Vec;
+
+/// Serializes `msg` and appends it to `out`. The storage header is only
+/// written if the message has one.
+///
+/// The header type flags are derived from which optional parts are present,
+/// only the endianness and version are taken from the message's `htyp`.
+/// [`Message::msb_first`] is not written, the payload is copied as it is.
+///
+/// # Panics
+///
+/// Panics if the message does not fit into the 16-bit length field, or if a
+/// reserved message type does not fit into the extended header.
+pub fn write_message(msg: &Message, out: &mut Vec<u8>) {
+    let Message {
+        storage_header,
+        standard_header,
+        extensions,
+        extended_header,
+        payload,
+        msb_first: _,
+    } = msg;
+
+    if let Some(storage_header) = storage_header {
+        out.extend_from_slice(&storage_header.pattern);
+        out.extend_from_slice(&storage_header.timestamp.secs.to_le_bytes());
+        out.extend_from_slice(&storage_header.timestamp.micros.to_le_bytes());
+        out.extend_from_slice(&storage_header.ecu.to_bytes());
+    }
+
+    let header_type = standard_header.header_type();
+    let htyp = HeaderType {
+        use_extended_header: extended_header.is_some(),
+        msb_first: header_type.msb_first,
+        with_ecu_id: extensions.ecu_id.is_some(),
+        with_session_id: extensions.session_id.is_some(),
+        with_timestamp: extensions.timestamp.is_some(),
+        version: header_type.version,
+    }
+    .to_raw();
+
+    let mut len = 4;
+
+    if extended_header.is_some() {
+        len += 10;
+    }
+
+    if extensions.ecu_id.is_some() {
+        len += 4;
+    }
+
+    if extensions.session_id.is_some() {
+        len += 4;
+    }
+
+    if extensions.timestamp.is_some() {
+        len += 4;
+    }
+
+    len += payload.len();
+
+    let len = u16::try_from(len).expect("message too long for the DLT length field");
+
+    out.push(htyp);
+    out.push(standard_header.mcnt);
+    out.extend_from_slice(&len.to_be_bytes());
+
+    if let Some(ecu_id) = extensions.ecu_id {
+        out.extend_from_slice(&ecu_id.to_bytes());
+    }
+
+    if let Some(session_id) = extensions.session_id {
+        out.extend_from_slice(&session_id.to_be_bytes());
+    }
+
+    if let Some(timestamp) = extensions.timestamp {
+        out.extend_from_slice(&timestamp.to_be_bytes());
+    }
+
+    if let Some(extended_header) = extended_header {
+        out.push(
+            extended_header
+                .message_type
+                .to_msin(extended_header.verbose),
+        );
+        out.push(extended_header.noar);
+        out.extend_from_slice(&extended_header.apid.to_bytes());
+        out.extend_from_slice(&extended_header.ctid.to_bytes());
+    }
+
+    out.extend_from_slice(payload);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse_message_with, Argument, ExtendedHeader, Id4, LogTypeInfo, MessageBuilder,
+        MessageInfo, OwnedMessage, ParseOptions, StandardExtensions, StandardHeader, StorageHeader,
+        StorageTime, STORAGE_MAGIC,
+    };
+    use alloc::format;
+    use proptest::prelude::*;
+
+    fn id() -> impl Strategy<Value = Id4> {
+        "[A-Z0-9]{0,4}".prop_map(|it| it.parse().unwrap())
+    }
+
+    fn level() -> impl Strategy<Value = LogTypeInfo> {
+        prop_oneof![
+            Just(LogTypeInfo::Fatal),
+            Just(LogTypeInfo::Error),
+            Just(LogTypeInfo::Warn),
+            Just(LogTypeInfo::Info),
+            Just(LogTypeInfo::Debug),
+            Just(LogTypeInfo::Verbose),
+        ]
+    }
+
+    fn extended_header() -> impl Strategy<Value = ExtendedHeader> {
+        let message_type = prop_oneof![
+            level().prop_map(|level| MessageInfo::Log { level }),
+            (0..8u8, 0..16u8).prop_map(|(mstp, mtin)| MessageInfo::from_raw(mstp, mtin)),
+        ];
+
+        (message_type, any::<bool>(), any::<u8>(), id(), id()).prop_map(
+            |(message_type, verbose, noar, apid, ctid)| ExtendedHeader {
+                message_type,
+                verbose,
+                noar,
+                apid,
+                ctid,
+            },
+        )
+    }
+
+    /// Messages with any combination of optional headers, the types and log
+    /// levels as encoded on the wire and arbitrary payloads.
+    fn message() -> impl Strategy<Value = OwnedMessage> {
+        let storage_header = proptest::option::of((any::<u32>(), 0..1_000_000u32, id()).prop_map(
+            |(secs, micros, ecu)| StorageHeader {
+                pattern: STORAGE_MAGIC,
+                timestamp: StorageTime { secs, micros },
+                ecu,
+            },
+        ));
+        let extensions = (
+            proptest::option::of(id()),
+            proptest::option::of(any::<u32>()),
+            proptest::option::of(any::<u32>()),
+        )
+            .prop_map(|(ecu_id, session_id, timestamp)| StandardExtensions {
+                ecu_id,
+                session_id,
+                timestamp,
+            });
+
+        (
+            storage_header,
+            any::<bool>(),
+            any::<u8>(),
+            extensions,
+            proptest::option::of(extended_header()),
+            proptest::collection::vec(any::<u8>(), 0..64),
+        )
+            .prop_map(
+                |(storage_header, msb_first, mcnt, extensions, extended_header, payload)| {
+                    let htyp = HeaderType {
+                        use_extended_header: false,
+                        msb_first,
+                        with_ecu_id: false,
+                        with_session_id: false,
+                        with_timestamp: false,
+                        version: 1,
+                    }
+                    .to_raw();
+
+                    OwnedMessage {
+                        storage_header,
+                        // The encoder fills in the flags and the length.
+                        standard_header: StandardHeader { htyp, mcnt, len: 0 },
+                        extensions,
+                        extended_header,
+                        payload,
+                        msb_first: None,
+                    }
+                },
+            )
+    }
+
+    #[test]
+    fn reserved_message_types_round_trip() {
+        let mut message = MessageBuilder::log(LogTypeInfo::Info).build();
+        let reserved = MessageInfo::Reserved { mstp: 7, mtin: 15 };
+        message.extended_header.as_mut().unwrap().message_type = reserved;
+        let mut encoded = Vec::new();
+        write_message(&message.as_message(), &mut encoded);
+
+        let (parsed, _) = parse_message_with(&encoded, &ParseOptions::new()).unwrap();
+        let extended_header = parsed.extended_header.unwrap();
+        assert_eq!(extended_header.raw_type(), 7);
+        assert_eq!(extended_header.raw_subtype(), 15);
+        assert!(extended_header.verbose);
+    }
+
+    #[test]
+    #[should_panic = "does not fit into the extended header"]
+    fn reserved_message_types_out_of_range() {
+        let mut message = MessageBuilder::log(LogTypeInfo::Info).build();
+        let reserved = MessageInfo::Reserved { mstp: 8, mtin: 0 };
+        message.extended_header.as_mut().unwrap().message_type = reserved;
+        write_message(&message.as_message(), &mut Vec::new());
+    }
+
+    #[test]
+    fn payload_round_trips_unchanged() {
+        let payload: Vec<u8> = (0..32).collect();
+        let message = Message {
+            storage_header: Some(StorageHeader {
+                pattern: STORAGE_MAGIC,
+                timestamp: StorageTime { secs: 1, micros: 2 },
+                ecu: Id4::new(*b"ECU1"),
+            }),
+            standard_header: StandardHeader {
+                htyp: 0x20,
+                mcnt: 3,
+                len: 0,
+            },
+            extensions: StandardExtensions {
+                ecu_id: Some(Id4::new(*b"ECU1")),
+                session_id: Some(4),
+                timestamp: Some(5),
+            },
+            extended_header: Some(ExtendedHeader {
+                message_type: MessageInfo::Log {
+                    level: LogTypeInfo::Info,
+                },
+                verbose: false,
+                noar: 0,
+                apid: Id4::new(*b"APP\0"),
+                ctid: Id4::new(*b"CTX\0"),
+            }),
+            payload: &payload,
+            msb_first: None,
+        };
+        let mut encoded = Vec::new();
+        write_message(&message, &mut encoded);
+
+        // Storage, standard, extensions and extended header, then the payload
+        // without anything in between.
+        assert_eq!(encoded.len(), 16 + 4 + 12 + 10 + payload.len());
+        assert!(encoded.ends_with(&payload));
+
+        let (parsed, _) = parse_message_with(&encoded, &ParseOptions::new()).unwrap();
+        assert_eq!(parsed.payload, payload);
+    }
+
+    proptest! {
+        #[test]
+        fn encoded_messages_parse_back(message in message()) {
+            let message = message.as_message();
+            let mut encoded = Vec::new();
+            write_message(&message, &mut encoded);
+
+            let opts = ParseOptions::new().storage_header(message.storage_header.is_some());
+            let (parsed, rest) = parse_message_with(&encoded, &opts).unwrap();
+            prop_assert!(rest.is_empty());
+            prop_assert_eq!(parsed.total_len(), encoded.len());
+            prop_assert_eq!(parsed.is_big_endian(), message.is_big_endian());
+
+            let mut reencoded = Vec::new();
+            write_message(&parsed, &mut reencoded);
+            prop_assert_eq!(&reencoded, &encoded);
+
+            // Everything but the length, which the encoder computed.
+            let parsed = Message {
+                standard_header: StandardHeader {
+                    len: 0,
+                    ..parsed.standard_header
+                },
+                ..parsed
+            };
+            let mut expected = message;
+            expected.standard_header.htyp = reencoded[if opts.storage_header { 16 } else { 0 }];
+            prop_assert_eq!(format!("{parsed:?}"), format!("{expected:?}"));
+        }
+
+        #[test]
+        fn built_arguments_decode(
+            level in level(),
+            value in any::<u32>(),
+            signed in any::<i64>(),
+            flag in any::<bool>(),
+            text in "\\PC{0,16}",
+            raw in proptest::collection::vec(any::<u8>(), 0..16),
+        ) {
+            let message = MessageBuilder::log(level)
+                .arg_u32(value)
+                .arg_i64(signed)
+                .arg_bool(flag)
+                .arg_str(&text)
+                .arg_raw(&raw)
+                .build();
+            let mut encoded = Vec::new();
+            write_message(&message.as_message(), &mut encoded);
+
+            let (parsed, _) = parse_message_with(&encoded, &ParseOptions::new()).unwrap();
+            prop_assert_eq!(parsed.log_level(), Some(level));
+
+            let arguments: Vec<_> = parsed.argument_iter().unwrap().map(Result::unwrap).collect();
+            prop_assert!(
+                matches!(arguments[0], Argument::UnsignedInt { value: it, .. } if it == u64::from(value)),
+                "unexpected {:?}",
+                arguments[0]
+            );
+            prop_assert!(
+                matches!(arguments[1], Argument::SignedInt { value: it, .. } if it == signed),
+                "unexpected {:?}",
+                arguments[1]
+            );
+            prop_assert!(
+                matches!(arguments[2], Argument::Bool { value: it, .. } if it == flag),
+                "unexpected {:?}",
+                arguments[2]
+            );
+            prop_assert_eq!(arguments[3].as_str().unwrap(), text.as_str());
+            prop_assert_eq!(arguments[4].as_bytes().unwrap(), &raw[..]);
+            prop_assert_eq!(arguments.len(), 5);
+        }
+    }
+}
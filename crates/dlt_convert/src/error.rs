@@ -0,0 +1,146 @@
+use core::fmt;
+
+/// Error returned when a DLT message cannot be parsed.
+///
+/// Every variant carries the byte offset, relative to the start of the slice
+/// handed to the parser, at which the problem was detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DltParseError {
+    UnexpectedEof {
+        offset: usize,
+    },
+    BadStorageMagic {
+        offset: usize,
+        found: [u8; 4],
+    },
+    /// The length field of the standard header at `offset` is `len`, less
+    /// than the `headers` bytes of headers it has to cover.
+    LengthUnderflow {
+        offset: usize,
+        len: usize,
+        headers: usize,
+    },
+    UnsupportedVersion {
+        offset: usize,
+        version: u8,
+    },
+    /// The microseconds of the storage header at `offset` are not below one
+    /// million.
+    InvalidMicros {
+        offset: usize,
+        micros: u32,
+    },
+}
+
+impl DltParseError {
+    pub fn offset(&self) -> usize {
+        match *self {
+            Self::UnexpectedEof { offset }
+            | Self::BadStorageMagic { offset, .. }
+            | Self::LengthUnderflow { offset, .. }
+            | Self::UnsupportedVersion { offset, .. }
+            | Self::InvalidMicros { offset, .. } => offset,
+        }
+    }
+
+    pub(crate) fn offset_by(mut self, by: usize) -> Self {
+        match &mut self {
+            Self::UnexpectedEof { offset }
+            | Self::BadStorageMagic { offset, .. }
+            | Self::LengthUnderflow { offset, .. }
+            | Self::UnsupportedVersion { offset, .. }
+            | Self::InvalidMicros { offset, .. } => *offset += by,
+        }
+
+        self
+    }
+}
+
+impl fmt::Display for DltParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof { offset } => {
+                write!(f, "unexpected end of input at byte {offset}")
+            }
+            Self::BadStorageMagic { offset, found } => {
+                write!(f, "bad storage header magic {found:02x?} at byte {offset}")
+            }
+            Self::LengthUnderflow {
+                offset,
+                len,
+                headers,
+            } => {
+                write!(
+                    f,
+                    "message length {len} is shorter than its {headers} header bytes at byte {offset}"
+                )
+            }
+            Self::UnsupportedVersion { offset, version } => {
+                write!(f, "unsupported protocol version {version} at byte {offset}")
+            }
+            Self::InvalidMicros { offset, micros } => {
+                write!(f, "invalid storage microseconds {micros} at byte {offset}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for DltParseError {}
+
+/// Error returned when a verbose argument cannot be decoded.
+///
+/// Offsets are relative to the start of the payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgumentError {
+    /// The payload ended in the middle of the argument at `offset`, for
+    /// example because it holds fewer arguments than `noar` claims.
+    UnexpectedEof { offset: usize },
+    /// The argument at `offset` has a type that cannot be decoded.
+    Unsupported { offset: usize, type_info: u32 },
+    /// The payload continues after the last argument.
+    TrailingBytes { offset: usize },
+}
+
+impl ArgumentError {
+    pub fn offset(&self) -> usize {
+        match *self {
+            Self::UnexpectedEof { offset }
+            | Self::Unsupported { offset, .. }
+            | Self::TrailingBytes { offset } => offset,
+        }
+    }
+
+    pub(crate) fn offset_by(mut self, by: usize) -> Self {
+        match &mut self {
+            Self::UnexpectedEof { offset }
+            | Self::Unsupported { offset, .. }
+            | Self::TrailingBytes { offset } => *offset += by,
+        }
+
+        self
+    }
+}
+
+impl fmt::Display for ArgumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof { offset } => {
+                write!(f, "payload ends inside the argument at byte {offset}")
+            }
+            Self::Unsupported { offset, type_info } => {
+                write!(
+                    f,
+                    "unsupported argument type info {type_info:#010x} at byte {offset}"
+                )
+            }
+            Self::TrailingBytes { offset } => {
+                write!(
+                    f,
+                    "unexpected bytes after the last argument at byte {offset}"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for ArgumentError {}
@@ -0,0 +1,97 @@
+use crate::{Message, MessageInfo};
+use std::{fmt::Write, io};
+
+/// Writes `messages` as CSV to `out`, starting with a header row.
+///
+/// The columns are timestamp, ecu, apid, ctid, level, counter and payload,
+/// where the payload is the text of
+/// [`write_payload_text`](Message::write_payload_text). Missing values are
+/// left empty.
+pub fn write_csv<'a, W: io::Write>(
+    messages: impl Iterator<Item = Message<'a>>,
+    out: W,
+) -> io::Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record([
+        "timestamp",
+        "ecu",
+        "apid",
+        "ctid",
+        "level",
+        "counter",
+        "payload",
+    ])?;
+
+    let mut timestamp = String::new();
+    let mut payload = String::new();
+
+    for message in messages {
+        timestamp.clear();
+        if let Some(storage_header) = &message.storage_header {
+            write!(timestamp, "{}", storage_header.timestamp)
+                .expect("writing to a String cannot fail");
+        }
+
+        payload.clear();
+        message
+            .write_payload_text(&mut payload)
+            .expect("writing to a String cannot fail");
+
+        let ecu = message.ecu().map(|it| it.to_string()).unwrap_or_default();
+
+        let (apid, ctid, level) = match &message.extended_header {
+            Some(extended_header) => (
+                extended_header.apid.to_string(),
+                extended_header.ctid.to_string(),
+                match &extended_header.message_type {
+                    MessageInfo::Log { level } => level.as_str(),
+                    _ => "",
+                },
+            ),
+            None => (String::new(), String::new(), ""),
+        };
+
+        writer.write_record([
+            timestamp.as_str(),
+            &ecu,
+            &apid,
+            &ctid,
+            level,
+            &message.standard_header.mcnt.to_string(),
+            payload.as_str(),
+        ])?;
+    }
+
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{capture_bytes, parse_message, LogTypeInfo, MessageBuilder, StorageTime};
+
+    #[test]
+    fn header_and_quoted_row() {
+        let message = MessageBuilder::log(LogTypeInfo::Warn)
+            .ecu("ECU1")
+            .app("APP")
+            .ctx("CTX")
+            .timestamp(StorageTime {
+                secs: 1_700_000_000,
+                micros: 1234,
+            })
+            .counter(5)
+            .arg_str("say \"hi\", then\nbye");
+        let data = capture_bytes([message]);
+        let (message, _) = parse_message(&data).unwrap();
+
+        let mut out = Vec::new();
+        write_csv([message].into_iter(), &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "timestamp,ecu,apid,ctid,level,counter,payload\n\
+             2023/11/14 22:13:20.001234,ECU1,APP,CTX,warn,5,\"say \"\"hi\"\", then\nbye\"\n"
+        );
+    }
+}
@@ -0,0 +1,290 @@
+use crate::{ArgumentType, Id4, MemoryCatalog, MessageCatalog, MessageDef};
+use roxmltree::{Document, Node};
+use std::{collections::HashMap, fmt};
+
+/// Non-verbose message definitions read from a FIBEX file, see
+/// [`load_fibex`].
+#[derive(Debug, Clone, Default)]
+pub struct FibexCatalog {
+    catalog: MemoryCatalog,
+}
+
+impl MessageCatalog for FibexCatalog {
+    fn lookup(&self, apid: Id4, ctid: Id4, id: u32) -> Option<&MessageDef> {
+        self.catalog.lookup(apid, ctid, id)
+    }
+}
+
+#[derive(Debug)]
+pub enum FibexError {
+    Xml(roxmltree::Error),
+    /// A frame ID is not of the form `ID_<message id>`.
+    InvalidFrameId {
+        id: String,
+    },
+    /// An application or context ID is longer than 4 bytes or not ASCII.
+    InvalidId {
+        id: String,
+    },
+    /// An `ID-REF` points at an element that does not exist.
+    MissingReference {
+        id: String,
+    },
+    /// A signal has a type that cannot be decoded.
+    UnsupportedSignal {
+        id: String,
+    },
+}
+
+impl fmt::Display for FibexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Xml(err) => write!(f, "invalid FIBEX XML: {err}"),
+            Self::InvalidFrameId { id } => write!(f, "invalid frame ID {id:?}"),
+            Self::InvalidId { id } => write!(f, "invalid application or context ID {id:?}"),
+            Self::MissingReference { id } => write!(f, "reference to unknown element {id:?}"),
+            Self::UnsupportedSignal { id } => write!(f, "unsupported signal type of {id:?}"),
+        }
+    }
+}
+
+impl std::error::Error for FibexError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Xml(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<roxmltree::Error> for FibexError {
+    fn from(err: roxmltree::Error) -> Self {
+        Self::Xml(err)
+    }
+}
+
+/// Reads the non-verbose message definitions of a FIBEX file.
+///
+/// Every `FRAME` with an ID of the form `ID_<message id>` becomes a
+/// definition. The application and context ID are taken from its
+/// manufacturer extension. Its PDUs, in sequence order, contribute either
+/// their description as static text or their signals as arguments. Signal
+/// types are taken from the signal ID (`S_UINT32`, ...) or the base data type
+/// of its coding (`A_UINT32`, ...).
+pub fn load_fibex(xml: &str) -> Result<FibexCatalog, FibexError> {
+    let document = Document::parse(xml)?;
+
+    let mut elements = HashMap::new();
+    for node in document.descendants() {
+        if let ("PDU" | "SIGNAL" | "CODING", Some(id)) =
+            (node.tag_name().name(), node.attribute("ID"))
+        {
+            elements.insert(id, node);
+        }
+    }
+
+    let lookup = |id: &str| {
+        elements
+            .get(id)
+            .copied()
+            .ok_or_else(|| FibexError::MissingReference { id: id.to_owned() })
+    };
+
+    let mut catalog = MemoryCatalog::new();
+
+    for frame in document
+        .descendants()
+        .filter(|it| it.tag_name().name() == "FRAME")
+    {
+        let frame_id = frame.attribute("ID").unwrap_or_default();
+        let id = frame_id
+            .strip_prefix("ID_")
+            .and_then(|it| it.parse().ok())
+            .ok_or_else(|| FibexError::InvalidFrameId {
+                id: frame_id.to_owned(),
+            })?;
+
+        let extension = child(frame, "MANUFACTURER-EXTENSION");
+        let apid = parse_id(extension.and_then(|it| child_text(it, "APPLICATION_ID")))?;
+        let ctid = parse_id(extension.and_then(|it| child_text(it, "CONTEXT_ID")))?;
+
+        let mut pieces = Vec::new();
+        let mut arguments = Vec::new();
+
+        for pdu_ref in sorted_refs(frame, "PDU-INSTANCE", "PDU-REF") {
+            let pdu = lookup(pdu_ref)?;
+            let signal_refs = sorted_refs(pdu, "SIGNAL-INSTANCE", "SIGNAL-REF");
+
+            if signal_refs.is_empty() {
+                pieces.push(child_text(pdu, "DESC").unwrap_or_default());
+            }
+
+            for signal_ref in signal_refs {
+                let signal = lookup(signal_ref)?;
+                let coding = child(signal, "CODING-REF")
+                    .and_then(|it| it.attribute("ID-REF"))
+                    .map(lookup)
+                    .transpose()?;
+                let base_data_type = coding
+                    .and_then(|it| {
+                        it.descendants()
+                            .find(|it| it.tag_name().name() == "CODED-TYPE")
+                    })
+                    .and_then(|it| {
+                        it.attributes()
+                            .find(|it| it.name() == "BASE-DATA-TYPE")
+                            .map(|it| it.value())
+                    });
+
+                let ty = argument_type(signal_ref)
+                    .or_else(|| base_data_type.and_then(argument_type))
+                    .ok_or_else(|| FibexError::UnsupportedSignal {
+                        id: signal_ref.to_owned(),
+                    })?;
+
+                pieces.push("{}");
+                arguments.push(ty);
+            }
+        }
+
+        catalog.insert(MessageDef {
+            apid,
+            ctid,
+            id,
+            format: pieces.join(" "),
+            arguments,
+        });
+    }
+
+    Ok(FibexCatalog { catalog })
+}
+
+fn parse_id(id: Option<&str>) -> Result<Id4, FibexError> {
+    let id = id.unwrap_or_default();
+    id.parse()
+        .map_err(|_| FibexError::InvalidId { id: id.to_owned() })
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|it| it.tag_name().name() == name)
+}
+
+fn child_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name)?.text().map(str::trim)
+}
+
+/// The `ID-REF`s of the `ref_name` children of all `instance_name`
+/// descendants of `node`, ordered by their sequence number.
+fn sorted_refs<'a>(node: Node<'a, '_>, instance_name: &str, ref_name: &str) -> Vec<&'a str> {
+    let mut refs: Vec<_> = node
+        .descendants()
+        .filter(|it| it.tag_name().name() == instance_name)
+        .filter_map(|it| {
+            let sequence_number = child_text(it, "SEQUENCE-NUMBER")
+                .and_then(|it| it.parse::<u32>().ok())
+                .unwrap_or_default();
+            let id_ref = child(it, ref_name)?.attribute("ID-REF")?;
+
+            Some((sequence_number, id_ref))
+        })
+        .collect();
+
+    refs.sort_by_key(|&(sequence_number, _)| sequence_number);
+    refs.into_iter().map(|(_, id_ref)| id_ref).collect()
+}
+
+fn argument_type(name: &str) -> Option<ArgumentType> {
+    Some(match name {
+        "S_BOOL" | "A_BOOLEAN" => ArgumentType::Bool,
+        "S_SINT8" | "A_INT8" => ArgumentType::I8,
+        "S_SINT16" | "A_INT16" => ArgumentType::I16,
+        "S_SINT32" | "A_INT32" => ArgumentType::I32,
+        "S_SINT64" | "A_INT64" => ArgumentType::I64,
+        "S_UINT8" | "A_UINT8" => ArgumentType::U8,
+        "S_UINT16" | "A_UINT16" => ArgumentType::U16,
+        "S_UINT32" | "A_UINT32" => ArgumentType::U32,
+        "S_UINT64" | "A_UINT64" => ArgumentType::U64,
+        "S_FLOA32" | "A_FLOAT32" => ArgumentType::F32,
+        "S_FLOA64" | "A_FLOAT64" => ArgumentType::F64,
+        "S_STRG_ASCII" | "S_STRG_UTF8" | "A_ASCIISTRING" | "A_UNICODE2STRING" => {
+            ArgumentType::String
+        }
+        "S_RAWD" | "S_RAW" | "A_BYTEFIELD" => ArgumentType::Raw,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse_message_no_storage, write_message, ExtendedHeader, LogTypeInfo, Message, MessageInfo,
+        StandardExtensions, StandardHeader,
+    };
+
+    const FIBEX: &str = include_str!("../fixtures/tiny.fibex.xml");
+
+    #[test]
+    fn tiny_fibex() {
+        let catalog = load_fibex(FIBEX).unwrap();
+        let apid = "APP".parse().unwrap();
+        let ctid = "CTX".parse().unwrap();
+
+        assert_eq!(
+            catalog.lookup(apid, ctid, 10),
+            Some(&MessageDef {
+                apid,
+                ctid,
+                id: 10,
+                format: "speed {} {} {}".to_owned(),
+                arguments: vec![ArgumentType::U32, ArgumentType::F64, ArgumentType::String],
+            })
+        );
+        assert_eq!(catalog.lookup(apid, ctid, 11), None);
+    }
+
+    #[test]
+    fn formats_a_matching_non_verbose_message() {
+        let catalog = load_fibex(FIBEX).unwrap();
+
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&10u32.to_le_bytes());
+        payload.extend_from_slice(&120u32.to_le_bytes());
+        payload.extend_from_slice(&1.5f64.to_le_bytes());
+        payload.extend_from_slice(&3u16.to_le_bytes());
+        payload.extend_from_slice(b"ok\0");
+
+        let message = Message {
+            storage_header: None,
+            standard_header: StandardHeader {
+                htyp: 0x20,
+                mcnt: 0,
+                len: 0,
+            },
+            extensions: StandardExtensions {
+                ecu_id: None,
+                session_id: None,
+                timestamp: None,
+            },
+            extended_header: Some(ExtendedHeader {
+                message_type: MessageInfo::Log {
+                    level: LogTypeInfo::Info,
+                },
+                verbose: false,
+                noar: 0,
+                apid: "APP".parse().unwrap(),
+                ctid: "CTX".parse().unwrap(),
+            }),
+            payload: &payload,
+            msb_first: None,
+        };
+        let mut data = Vec::new();
+        write_message(&message, &mut data);
+        let (message, _) = parse_message_no_storage(&data).unwrap();
+
+        assert_eq!(
+            message.format_non_verbose(&catalog).as_deref(),
+            Some("speed 120 1.5 ok")
+        );
+    }
+}
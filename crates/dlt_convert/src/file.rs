@@ -0,0 +1,300 @@
+use crate::{
+    parse_message,
+    scan::{find_magic, message_len},
+    Message, OwnedMessage, StreamParser, STORAGE_MAGIC,
+};
+#[cfg(feature = "gzip")]
+use flate2::bufread::MultiGzDecoder;
+#[cfg(feature = "gzip")]
+use std::io::BufRead;
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Number of bytes [`follow`] compares to notice that a file was rewritten.
+/// Messages of one ECU often share most bytes, so this spans more than one
+/// storage header.
+const FOLLOW_TAIL_LEN: usize = 64;
+#[cfg(feature = "gzip")]
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Reads and parses all messages of the `.dlt` file at `path`, see
+/// [`parse_reader`].
+///
+/// With the `gzip` feature, files starting with the gzip magic are
+/// decompressed first, so `.dlt.gz` archives can be read as well.
+pub fn parse_file(path: &Path) -> io::Result<Vec<OwnedMessage>> {
+    #[cfg_attr(not(feature = "gzip"), allow(unused_mut))]
+    let mut reader = BufReader::new(File::open(path)?);
+
+    #[cfg(feature = "gzip")]
+    if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        return parse_reader(MultiGzDecoder::new(reader));
+    }
+
+    parse_reader(reader)
+}
+
+/// Decompresses the gzip file at `path` and parses all messages of the
+/// `.dlt` file it contains, see [`parse_reader`].
+#[cfg(feature = "gzip")]
+pub fn parse_gz_file(path: &Path) -> io::Result<Vec<OwnedMessage>> {
+    parse_reader(MultiGzDecoder::new(BufReader::new(File::open(path)?)))
+}
+
+/// Reads and parses all storage framed messages from `reader`.
+///
+/// Errors of the reader are returned as is. Parse errors are returned as
+/// [`io::ErrorKind::InvalidData`] wrapping a
+/// [`DltParseError`](crate::DltParseError), and a reader that ends in the
+/// middle of a message yields [`io::ErrorKind::UnexpectedEof`], like
+/// [`StreamParser::next_message`].
+pub fn parse_reader<R: Read>(reader: R) -> io::Result<Vec<OwnedMessage>> {
+    let mut parser = StreamParser::new(reader);
+    let mut messages = Vec::new();
+
+    while let Some(message) = parser.next_message()? {
+        messages.push(message);
+    }
+
+    Ok(messages)
+}
+
+/// Calls `cb` with every message of the `.dlt` file at `path`, then keeps
+/// polling the file and calls it with every message appended later, like
+/// `tail -f`. Only returns on IO errors.
+///
+/// A message is only passed on once it has been written completely. Messages
+/// that cannot be parsed are skipped, continuing at the next storage header
+/// like [`MessageIter::with_resync`](crate::MessageIter::with_resync).
+///
+/// The file is reopened and read from the start if the bytes last read
+/// changed, because it was truncated and possibly written again since, as
+/// with `copytruncate` log rotation. Once everything written so far has been
+/// read, the same happens if `path` now names a different file, because it
+/// was rotated. A missing file is waited for.
+pub fn follow(path: &Path, mut cb: impl FnMut(Message<'_>)) -> io::Result<()> {
+    let mut file = File::open(path)?;
+    let mut position = 0;
+    let mut buffer = Vec::new();
+    let mut tail = Vec::new();
+
+    loop {
+        if !still_ends_with(&mut file, position, &tail)? {
+            file = File::open(path)?;
+            position = 0;
+            buffer.clear();
+            tail.clear();
+        }
+
+        let read = file.read_to_end(&mut buffer)?;
+        position += read as u64;
+        tail.extend_from_slice(&buffer[buffer.len() - read..]);
+        tail.drain(..tail.len().saturating_sub(FOLLOW_TAIL_LEN));
+
+        let mut rest = &buffer[..];
+        loop {
+            match message_len(rest, &STORAGE_MAGIC) {
+                Some(len) if len <= rest.len() => match parse_message(&rest[..len]) {
+                    Ok((message, _)) => {
+                        cb(message);
+                        rest = &rest[len..];
+                    }
+                    // The length may be garbage as well, do not trust it.
+                    Err(_) => {
+                        let skip = find_magic(&rest[1..], &STORAGE_MAGIC).map_or(len, |it| 1 + it);
+                        rest = &rest[skip..];
+                    }
+                },
+                // Wait for the rest of the message.
+                Some(_) => break,
+                // Not at a storage header, skip to the next one. The last
+                // bytes may be the start of a magic still being written.
+                None if rest.len() >= 20 => {
+                    let skip =
+                        find_magic(&rest[1..], &STORAGE_MAGIC).map_or(rest.len() - 3, |it| 1 + it);
+                    rest = &rest[skip..];
+                }
+                None => break,
+            }
+        }
+
+        let consumed = buffer.len() - rest.len();
+        buffer.drain(..consumed);
+
+        if read == 0 {
+            let rotated = match fs::metadata(path) {
+                Ok(metadata) => !same_file(&file.metadata()?, &metadata),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => false,
+                Err(err) => return Err(err),
+            };
+
+            if rotated {
+                file = File::open(path)?;
+                position = 0;
+                buffer.clear();
+                tail.clear();
+                continue;
+            }
+
+            thread::sleep(FOLLOW_POLL_INTERVAL);
+        }
+    }
+}
+
+/// Whether the bytes of `file` in front of `position` still are `tail`, and
+/// moves to `position` if they are.
+fn still_ends_with(file: &mut File, position: u64, tail: &[u8]) -> io::Result<bool> {
+    let mut current = vec![0; tail.len()];
+    file.seek(SeekFrom::Start(position - tail.len() as u64))?;
+
+    match file.read_exact(&mut current) {
+        Ok(()) => Ok(current == tail),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Whether both describe the same file. Without inode numbers only the size
+/// can tell files apart, see [`follow`].
+#[cfg(unix)]
+fn same_file(a: &fs::Metadata, b: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+#[cfg(not(unix))]
+fn same_file(_: &fs::Metadata, _: &fs::Metadata) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{capture_bytes, info_message};
+    use std::{
+        path::PathBuf,
+        process,
+        sync::mpsc::{self, Receiver},
+    };
+
+    fn capture(counters: impl IntoIterator<Item = u8>) -> Vec<u8> {
+        capture_bytes(counters.into_iter().map(info_message))
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("dlt_convert_{}_{name}.dlt", process::id()))
+    }
+
+    fn spawn_follow(path: &Path) -> Receiver<u8> {
+        let (sender, receiver) = mpsc::channel();
+        let path = path.to_owned();
+        thread::spawn(move || {
+            follow(&path, |message| {
+                let _ = sender.send(message.standard_header.mcnt);
+            })
+        });
+        receiver
+    }
+
+    fn receive(receiver: &Receiver<u8>, count: usize) -> Vec<u8> {
+        (0..count)
+            .map(|_| receiver.recv_timeout(Duration::from_secs(5)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn follow_restarts_after_truncation() {
+        let path = temp_path("truncated");
+        fs::write(&path, capture(0..3)).unwrap();
+
+        let receiver = spawn_follow(&path);
+        assert_eq!(receive(&receiver, 3), [0, 1, 2]);
+
+        fs::write(&path, capture([10])).unwrap();
+        assert_eq!(receive(&receiver, 1), [10]);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn follow_restarts_after_truncation_and_regrowth() {
+        let path = temp_path("regrown");
+        fs::write(&path, capture(0..3)).unwrap();
+
+        let receiver = spawn_follow(&path);
+        assert_eq!(receive(&receiver, 3), [0, 1, 2]);
+
+        // Same file, larger than before the next poll.
+        fs::write(&path, capture(10..16)).unwrap();
+        assert_eq!(receive(&receiver, 6), [10, 11, 12, 13, 14, 15]);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn follow_skips_garbage_in_front_of_a_message() {
+        let path = temp_path("garbage");
+        let mut data = capture([0]);
+        // An extended header is announced but does not fit into the length,
+        // which reaches into the next message.
+        data.extend_from_within(..16);
+        data.extend_from_slice(&[0x21, 0, 0, 8]);
+        data.extend(capture([1]));
+        fs::write(&path, data).unwrap();
+
+        let receiver = spawn_follow(&path);
+        assert_eq!(receive(&receiver, 2), [0, 1]);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follow_restarts_after_rotation_to_a_larger_file() {
+        let path = temp_path("rotated");
+        let rotated = temp_path("rotated_next");
+        fs::write(&path, capture(0..2)).unwrap();
+
+        let receiver = spawn_follow(&path);
+        assert_eq!(receive(&receiver, 2), [0, 1]);
+
+        fs::write(&rotated, capture(10..14)).unwrap();
+        fs::rename(&rotated, &path).unwrap();
+        assert_eq!(receive(&receiver, 4), [10, 11, 12, 13]);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn parse_gzipped_file() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&capture(0..3)).unwrap();
+        let path = temp_path("gzipped");
+        fs::write(&path, encoder.finish().unwrap()).unwrap();
+
+        let counters = |messages: Vec<OwnedMessage>| -> Vec<u8> {
+            messages
+                .iter()
+                .map(|message| message.as_message().standard_header.mcnt)
+                .collect()
+        };
+        assert_eq!(counters(parse_gz_file(&path).unwrap()), [0, 1, 2]);
+        assert_eq!(counters(parse_file(&path).unwrap()), [0, 1, 2]);
+
+        fs::write(&path, capture(0..3)).unwrap();
+        assert_eq!(counters(parse_file(&path).unwrap()), [0, 1, 2]);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,292 @@
+use crate::{
+    parse_message_with,
+    scan::{message_len, peek_extended_header},
+    DltParseError, ExtendedHeader, Id4, LogTypeInfo, Message, MessageInfo, MessageIter,
+    ParseOptions,
+};
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+/// Selects messages by application ID, context ID and log level.
+///
+/// Criteria that are not set match every message. Messages without an
+/// extended header only match a filter without any criteria.
+#[derive(Debug, Clone, Default)]
+pub struct MessageFilter {
+    app_id: Option<Id4>,
+    ctx_id: Option<Id4>,
+    min_level: Option<LogTypeInfo>,
+}
+
+impl MessageFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only keep messages of this application ID. IDs given as text can be
+    /// parsed into an [`Id4`], which rejects those that could never match.
+    pub fn app_id(mut self, app_id: Id4) -> Self {
+        self.app_id = Some(app_id);
+        self
+    }
+
+    /// Only keep messages of this context ID.
+    pub fn ctx_id(mut self, ctx_id: Id4) -> Self {
+        self.ctx_id = Some(ctx_id);
+        self
+    }
+
+    /// Only keep log messages that are at least as severe as `level`.
+    pub fn min_level(mut self, level: LogTypeInfo) -> Self {
+        self.min_level = Some(level);
+        self
+    }
+
+    pub fn matches(&self, message: &Message) -> bool {
+        self.matches_extended_header(message.extended_header.as_ref())
+    }
+
+    /// All criteria only look at the extended header, which allows
+    /// [`filter_file`] to reject messages without parsing them.
+    fn matches_extended_header(&self, extended_header: Option<&ExtendedHeader>) -> bool {
+        let Some(extended_header) = extended_header else {
+            return self.app_id.is_none() && self.ctx_id.is_none() && self.min_level.is_none();
+        };
+
+        if self.app_id.is_some_and(|it| extended_header.apid != it) {
+            return false;
+        }
+
+        if self.ctx_id.is_some_and(|it| extended_header.ctid != it) {
+            return false;
+        }
+
+        if let Some(min_level) = self.min_level {
+            let MessageInfo::Log { level } = extended_header.message_type else {
+                return false;
+            };
+
+            if level < min_level {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Appends the raw bytes of every message of `data` accepted by `filter` to
+/// `out`, keeping their original framing.
+///
+/// Messages are first checked using only their length field and extended
+/// header, and skipped without parsing the other headers if the filter
+/// rejects them. Only accepted messages are parsed in full, so a malformed
+/// message the filter rejects is skipped rather than reported.
+///
+/// Stops at the first parse error in an accepted message, or one whose storage
+/// header or length cannot be read; the messages before it have been copied by
+/// then.
+pub fn filter_file(
+    data: &[u8],
+    filter: &MessageFilter,
+    out: &mut Vec<u8>,
+) -> Result<(), DltParseError> {
+    filter_file_with(data, filter, &ParseOptions::default(), out)
+}
+
+/// Like [`filter_file`], parsing every message with `opts`.
+///
+/// Without storage headers there is no length to check up front, so every
+/// message is parsed in full.
+pub fn filter_file_with(
+    data: &[u8],
+    filter: &MessageFilter,
+    opts: &ParseOptions,
+    out: &mut Vec<u8>,
+) -> Result<(), DltParseError> {
+    let mut rest = data;
+
+    while !rest.is_empty() {
+        let len = if opts.storage_header {
+            message_len(rest, &opts.storage_magic)
+        } else {
+            None
+        };
+        if let Some(len) = len.filter(|&len| len <= rest.len()) {
+            let rejected = peek_extended_header(&rest[..len])
+                .is_some_and(|it| !filter.matches_extended_header(it.as_ref()));
+
+            if rejected {
+                rest = &rest[len..];
+                continue;
+            }
+        }
+
+        let (message, next) =
+            parse_message_with(rest, opts).map_err(|err| err.offset_by(data.len() - rest.len()))?;
+
+        if filter.matches(&message) {
+            out.extend_from_slice(&rest[..message.total_len()]);
+        }
+
+        rest = next;
+    }
+
+    Ok(())
+}
+
+impl<'a> MessageIter<'a> {
+    /// Skips messages rejected by `filter`. Parse errors are passed through.
+    ///
+    /// Like [`filter_file`], storage framed messages are checked on their
+    /// length field and extended header first, and rejected ones are skipped
+    /// without being parsed in full or validated.
+    pub fn filter_dlt(self, filter: &MessageFilter) -> FilteredMessages<'a, '_> {
+        FilteredMessages { iter: self, filter }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FilteredMessages<'a, 'f> {
+    iter: MessageIter<'a>,
+    filter: &'f MessageFilter,
+}
+
+impl<'a> Iterator for FilteredMessages<'a, '_> {
+    type Item = Result<Message<'a>, DltParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let filter = self.filter;
+
+        loop {
+            while self.iter.skip_if(|it| !filter.matches_extended_header(it)) {}
+
+            let item = self.iter.next()?;
+            if item.as_ref().map_or(true, |it| filter.matches(it)) {
+                return Some(item);
+            }
+        }
+    }
+}
+
+impl FusedIterator for FilteredMessages<'_, '_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{capture_bytes, corrupt_version, messages, MessageBuilder};
+
+    fn capture() -> Vec<u8> {
+        let messages = [
+            ("APP1", LogTypeInfo::Info),
+            ("APP2", LogTypeInfo::Error),
+            ("APP1", LogTypeInfo::Error),
+            ("APP1", LogTypeInfo::Debug),
+            ("APP2", LogTypeInfo::Fatal),
+        ];
+
+        capture_bytes(messages.into_iter().enumerate().map(|(i, (app, level))| {
+            MessageBuilder::log(level)
+                .ecu("ECU1")
+                .app(app)
+                .ctx("CTX")
+                .counter(i as u8)
+                .arg_u32(i as u32)
+        }))
+    }
+
+    fn counters<'a>(messages: impl Iterator<Item = Message<'a>>) -> Vec<u8> {
+        messages.map(|it| it.standard_header.mcnt).collect()
+    }
+
+    /// Breaks the version of the message at `index` of the capture.
+    fn break_message(data: &mut [u8], index: usize) {
+        let start = data.len() / 5 * index;
+        corrupt_version(data, start);
+    }
+
+    #[test]
+    fn filtered_iterator_matches_filtering_every_message() {
+        let data = capture();
+
+        for filter in [
+            MessageFilter::new(),
+            MessageFilter::new().app_id("APP1".parse().unwrap()),
+            MessageFilter::new().min_level(LogTypeInfo::Error),
+            MessageFilter::new()
+                .app_id("APP2".parse().unwrap())
+                .ctx_id("CTX".parse().unwrap()),
+            MessageFilter::new().ctx_id("NONE".parse().unwrap()),
+        ] {
+            let filtered = messages(&data).filter_dlt(&filter).map(Result::unwrap);
+            let expected = messages(&data)
+                .map(Result::unwrap)
+                .filter(|it| filter.matches(it));
+            assert_eq!(counters(filtered), counters(expected));
+        }
+    }
+
+    #[test]
+    fn filter_file_output_reparses_to_the_filtered_messages() {
+        let data = capture();
+        let len = data.len() / 5;
+
+        for (filter, expected) in [
+            (MessageFilter::new(), &[0, 1, 2, 3, 4][..]),
+            (
+                MessageFilter::new().app_id("APP1".parse().unwrap()),
+                &[0, 2, 3],
+            ),
+            (
+                MessageFilter::new().min_level(LogTypeInfo::Error),
+                &[1, 2, 4],
+            ),
+            (MessageFilter::new().ctx_id("NONE".parse().unwrap()), &[]),
+        ] {
+            let mut out = Vec::new();
+            filter_file(&data, &filter, &mut out).unwrap();
+
+            assert_eq!(counters(messages(&out).map(Result::unwrap)), expected);
+
+            let copied: Vec<u8> = expected
+                .iter()
+                .flat_map(|&i| &data[usize::from(i) * len..][..len])
+                .copied()
+                .collect();
+            assert_eq!(out, copied);
+        }
+    }
+
+    #[test]
+    fn rejected_messages_are_not_validated() {
+        let mut data = capture();
+        // Rejected by the filter below.
+        break_message(&mut data, 1);
+
+        let filter = MessageFilter::new().app_id("APP1".parse().unwrap());
+        let filtered = messages(&data).filter_dlt(&filter).map(Result::unwrap);
+        assert_eq!(counters(filtered), [0, 2, 3]);
+
+        let mut out = Vec::new();
+        filter_file(&data, &filter, &mut out).unwrap();
+        assert_eq!(counters(messages(&out).map(Result::unwrap)), [0, 2, 3]);
+    }
+
+    #[test]
+    fn accepted_messages_are_validated() {
+        let mut data = capture();
+        break_message(&mut data, 2);
+
+        let filter = MessageFilter::new().app_id("APP1".parse().unwrap());
+        let results: Vec<_> = messages(&data).filter_dlt(&filter).collect();
+        assert_eq!(results.len(), 2);
+        assert!(matches!(
+            results[1],
+            Err(DltParseError::UnsupportedVersion { version: 2, .. })
+        ));
+
+        let mut out = Vec::new();
+        assert!(filter_file(&data, &filter, &mut out).is_err());
+        assert_eq!(counters(messages(&out).map(Result::unwrap)), [0]);
+    }
+}
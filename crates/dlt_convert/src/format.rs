@@ -0,0 +1,414 @@
+use crate::{
+    strip_null,
+    verbose::{parse_static_argument, write_lossy_utf8, write_string},
+    Argument, Id4, Message, MessageCatalog, MessageInfo, OffsetTime, PayloadDecoder, StorageTime,
+};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::fmt::{self, Write};
+
+impl Message<'_> {
+    /// Formats the message like a line of the dlt-viewer text export.
+    ///
+    /// The columns are index, time, timestamp, count, ecuid, apid, ctid, type,
+    /// subtype, mode, argument count and payload, separated by single spaces.
+    /// Missing values are printed as `-`. Verbose payloads are printed as
+    /// their decoded arguments, anything else as lossy UTF-8.
+    pub fn format_line(&self, index: usize) -> String {
+        self.format_line_with(index, &[])
+    }
+
+    /// Like [`Message::format_line`], with the payload formatted by the first
+    /// of `decoders` that handles the message.
+    ///
+    /// The decoders are tried in order and the first text returned is used.
+    /// If none of them returns text, the payload is formatted like
+    /// [`Message::format_line`] does: verbose payloads as their decoded
+    /// arguments, non-verbose ones as lossy UTF-8 rather than hex, so text
+    /// logged without verbose mode stays readable. Pass a decoder that calls
+    /// [`Message::payload_hexdump`] to get hex instead.
+    pub fn format_line_with(&self, index: usize, decoders: &[&dyn PayloadDecoder]) -> String {
+        self.format_line_at(index, 0, decoders)
+    }
+
+    /// Like [`Message::format_line_with`], with the storage time shown at
+    /// `utc_offset` seconds east of UTC, see [`StorageTime::with_offset`].
+    pub fn format_line_at(
+        &self,
+        index: usize,
+        utc_offset: i32,
+        decoders: &[&dyn PayloadDecoder],
+    ) -> String {
+        let mut line = String::new();
+        self.write_line(index, utc_offset, decoders, &mut line)
+            .expect("writing to a String cannot fail");
+        line
+    }
+
+    fn write_line(
+        &self,
+        index: usize,
+        utc_offset: i32,
+        decoders: &[&dyn PayloadDecoder],
+        out: &mut impl Write,
+    ) -> fmt::Result {
+        write!(out, "{index} ")?;
+
+        match &self.storage_header {
+            Some(storage_header) => {
+                write!(out, "{} ", storage_header.timestamp.with_offset(utc_offset))?;
+            }
+            None => out.write_str("- ")?,
+        }
+
+        match self.extensions.timestamp {
+            Some(ticks) => write!(out, "{}.{:04} ", ticks / 10_000, ticks % 10_000)?,
+            None => out.write_str("- ")?,
+        }
+
+        write!(out, "{} ", self.standard_header.mcnt)?;
+
+        match self.ecu() {
+            Some(ecu) => write!(out, "{ecu} ")?,
+            None => out.write_str("- ")?,
+        }
+
+        match &self.extended_header {
+            Some(extended_header) => {
+                let (ty, subtype) = match &extended_header.message_type {
+                    MessageInfo::Log { level } => ("log", level.as_str()),
+                    MessageInfo::AppTrace { trace } => ("app_trace", trace.as_str()),
+                    MessageInfo::NwTrace { network } => ("nw_trace", network.as_str()),
+                    MessageInfo::Control { kind } => ("control", kind.as_str()),
+                    MessageInfo::Reserved { .. } => ("-", "-"),
+                };
+                let mode = if extended_header.verbose {
+                    "verbose"
+                } else {
+                    "non-verbose"
+                };
+
+                write!(
+                    out,
+                    "{} {} {ty} {subtype} {mode} {} ",
+                    extended_header.apid, extended_header.ctid, extended_header.noar
+                )?;
+            }
+            None => out.write_str("- - - - non-verbose 0 ")?,
+        }
+
+        match decoders.iter().find_map(|it| it.decode(self)) {
+            Some(text) => out.write_str(&text),
+            None => self.write_payload_text(out),
+        }
+    }
+
+    /// The payload as lossy UTF-8, including any trailing null bytes.
+    pub fn payload_text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(self.payload)
+    }
+
+    /// The payload as lossy UTF-8 without trailing null bytes, for payloads
+    /// holding C strings.
+    pub fn payload_text_trimmed(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(strip_null(self.payload))
+    }
+
+    /// Dumps the payload in rows of 16 bytes, each with its offset, the bytes
+    /// in hex and their printable ASCII characters, like `hexdump -C`.
+    pub fn payload_hexdump(&self) -> String {
+        let mut dump = String::new();
+
+        for (row, chunk) in self.payload.chunks(16).enumerate() {
+            write!(dump, "{:08x} ", row * 16).expect("writing to a String cannot fail");
+
+            for i in 0..16 {
+                if i % 8 == 0 {
+                    dump.push(' ');
+                }
+
+                match chunk.get(i) {
+                    Some(byte) => {
+                        write!(dump, "{byte:02x} ").expect("writing to a String cannot fail")
+                    }
+                    None => dump.push_str("   "),
+                }
+            }
+
+            dump.push_str(" |");
+            dump.extend(chunk.iter().map(|&it| {
+                if it.is_ascii_graphic() || it == b' ' {
+                    char::from(it)
+                } else {
+                    '.'
+                }
+            }));
+            dump.push_str("|\n");
+        }
+
+        dump
+    }
+
+    /// Formats a non-verbose message using its definition in `catalog`.
+    ///
+    /// Messages without an extended header are looked up with an empty
+    /// application and context ID. Returns `None` if the message is verbose,
+    /// has no definition or its payload does not match the definition.
+    pub fn format_non_verbose(&self, catalog: &impl MessageCatalog) -> Option<String> {
+        let id = self.non_verbose_message_id()?;
+        let (apid, ctid) = match &self.extended_header {
+            Some(extended_header) => (extended_header.apid, extended_header.ctid),
+            None => (Id4::default(), Id4::default()),
+        };
+        let def = catalog.lookup(apid, ctid, id)?;
+
+        let msb_first = self.is_big_endian();
+        let mut data = &self.payload[4..];
+        let mut arguments = Vec::with_capacity(def.arguments.len());
+
+        for &ty in &def.arguments {
+            let (argument, rest) = parse_static_argument(ty, data, msb_first).ok()?;
+            arguments.push(argument);
+            data = rest;
+        }
+
+        let mut text = String::new();
+        let mut arguments = arguments.iter();
+        let mut pieces = def.format.split("{}");
+
+        text.push_str(pieces.next().unwrap_or_default());
+
+        for piece in pieces {
+            match arguments.next() {
+                Some(argument) => write!(text, "{argument}"),
+                None => text.write_str("{}"),
+            }
+            .expect("writing to a String cannot fail");
+            text.push_str(piece);
+        }
+
+        Some(text)
+    }
+
+    /// Writes the payload as text into `out`, writing each argument as soon
+    /// as it is decoded rather than building intermediate strings.
+    ///
+    /// Verbose payloads are written as their arguments separated by spaces.
+    /// If an argument fails to decode, the rest of the payload starting at
+    /// that argument is written as lossy UTF-8 without trailing null bytes,
+    /// like non-verbose payloads are.
+    pub fn write_payload_text(&self, out: &mut impl Write) -> fmt::Result {
+        let Some(mut arguments) = self.argument_iter() else {
+            return write_lossy_utf8(strip_null(self.payload), out);
+        };
+
+        for i in 0.. {
+            let rest = arguments.remainder();
+            let Some(argument) = arguments.next() else {
+                break;
+            };
+
+            if i > 0 {
+                out.write_char(' ')?;
+            }
+
+            match argument {
+                Ok(argument) => write!(out, "{argument}")?,
+                Err(_) => return write_lossy_utf8(strip_null(rest), out),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats the value like dlt-viewer: numbers in decimal, booleans as `true`
+/// or `false`, strings verbatim and raw data as hex, followed by the unit if
+/// there is one.
+impl fmt::Display for Argument<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool { value, .. } => write!(f, "{value}"),
+            Self::SignedInt { value, .. } => write!(f, "{value}"),
+            Self::UnsignedInt { value, .. } => write!(f, "{value}"),
+            Self::Float { value, .. } => write!(f, "{value}"),
+            Self::FixedPoint { .. } => write!(f, "{}", self.scaled_value().unwrap_or_default()),
+            Self::String { value, coding, .. } => write_string(value, *coding, f),
+            Self::Raw { value, .. } => value.iter().try_for_each(|it| write!(f, "{it:02x}")),
+        }?;
+
+        match self.unit().filter(|it| !it.is_empty()) {
+            Some(unit) => write!(f, " {unit}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Formats as UTC `YYYY/MM/DD HH:MM:SS.uuuuuu`, see
+/// [`StorageTime::with_offset`] for local times.
+impl fmt::Display for StorageTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.with_offset(0).fmt(f)
+    }
+}
+
+impl fmt::Display for OffsetTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (secs, micros) = self.time.normalized();
+        let secs = secs + i64::from(self.offset);
+        let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+        let secs = secs.rem_euclid(86_400);
+
+        write!(
+            f,
+            "{year:04}/{month:02}/{day:02} {:02}:{:02}:{:02}.{micros:06}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )
+    }
+}
+
+/// Proleptic Gregorian date of the given number of days since 1970-01-01,
+/// after Howard Hinnant's `civil_from_days`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{capture_bytes, info_message, parse_message, LogTypeInfo, MessageBuilder};
+    use alloc::format;
+
+    fn non_verbose(payload: &[u8], f: impl FnOnce(&Message)) {
+        let data = capture_bytes([info_message(0)]);
+
+        let (mut message, _) = parse_message(&data).unwrap();
+        if let Some(extended_header) = &mut message.extended_header {
+            extended_header.verbose = false;
+        }
+        message.payload = payload;
+        f(&message);
+    }
+
+    #[test]
+    fn format_line_with_uses_the_first_decoder_that_handles_the_message() {
+        let declines = |_: &Message| None;
+        let upper = |msg: &Message| Some(msg.payload_text().to_uppercase());
+        let lower = |msg: &Message| Some(msg.payload_text().to_lowercase());
+
+        non_verbose(b"Text", |message| {
+            let line = message.format_line_with(0, &[&declines, &upper, &lower]);
+            assert!(line.ends_with(" non-verbose 0 TEXT"), "{line}");
+        });
+    }
+
+    #[test]
+    fn format_line_with_falls_back_to_lossy_utf8() {
+        let declines = |_: &Message| None;
+
+        non_verbose(b"text\xff\0", |message| {
+            let line = message.format_line_with(0, &[&declines]);
+            assert_eq!(line, message.format_line(0));
+            assert!(line.ends_with(" non-verbose 0 text\u{fffd}"), "{line}");
+        });
+    }
+
+    #[test]
+    fn trailing_zero_bytes_are_kept_in_the_raw_payload() {
+        // A message ID followed by a little-endian 0x0100 counter.
+        let payload = [7, 0, 0, 0, 0x00, 0x01, 0x00, 0x00];
+
+        non_verbose(&payload, |message| {
+            assert_eq!(message.payload, payload);
+            assert_eq!(message.non_verbose_message_id(), Some(7));
+            assert_eq!(message.payload_text().len(), 8);
+            assert_eq!(message.payload_text_trimmed(), "\u{7}\0\0\0\0\u{1}");
+        });
+
+        let message = MessageBuilder::log(LogTypeInfo::Info)
+            .arg_raw(&[0xab, 0x00])
+            .build();
+        let message = message.as_message();
+        let arguments = message.arguments().unwrap().unwrap();
+        assert_eq!(arguments[0].as_bytes(), Some(&[0xab, 0x00][..]));
+    }
+
+    #[test]
+    fn format_line_at_shifts_the_storage_time() {
+        let message = info_message(0)
+            .timestamp(StorageTime {
+                secs: 1_700_000_000,
+                micros: 1234,
+            })
+            .build();
+        let message = message.as_message();
+
+        assert!(message
+            .format_line(0)
+            .starts_with("0 2023/11/14 22:13:20.001234 "));
+        assert!(message
+            .format_line_at(0, 2 * 3600, &[])
+            .starts_with("0 2023/11/15 00:13:20.001234 "));
+        assert!(message
+            .format_line_at(0, -(5 * 3600 + 30 * 60), &[])
+            .starts_with("0 2023/11/14 16:43:20.001234 "));
+    }
+
+    #[test]
+    fn write_payload_text_falls_back_at_the_failing_argument() {
+        let built = info_message(0).arg_u32(7).arg_str("hi").build();
+        let mut message = built.as_message();
+
+        // Cut off the null terminator, so the string runs past the payload.
+        let payload = &built.payload[..built.payload.len() - 1];
+        message.payload = payload;
+
+        let mut text = String::new();
+        message.write_payload_text(&mut text).unwrap();
+        // The type info and value of the u32 take 8 bytes.
+        let rest = &payload[8..];
+        assert_eq!(text, format!("7 {}", String::from_utf8_lossy(rest)));
+
+        // Nothing decodes, so all of it is text: the type info 0x43 of the
+        // u32 and two null bytes.
+        message.payload = &payload[..3];
+        text.clear();
+        message.write_payload_text(&mut text).unwrap();
+        assert_eq!(text, "C");
+    }
+
+    #[test]
+    fn write_payload_text_reuses_one_buffer() {
+        let payloads: [&[u8]; 3] = [b"first\0", b"caf\xc3\xa9", b"bad \xff byte\0\0"];
+        let mut text = String::with_capacity(64);
+        let capacity = text.capacity();
+
+        for payload in payloads {
+            text.clear();
+            non_verbose(payload, |message| {
+                message.write_payload_text(&mut text).unwrap();
+            });
+
+            // The text the CLI used to allocate for every message.
+            assert_eq!(text, String::from_utf8_lossy(strip_null(payload)));
+        }
+
+        assert_eq!(text.capacity(), capacity);
+    }
+}
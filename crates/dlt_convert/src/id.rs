@@ -0,0 +1,164 @@
+use crate::strip_null;
+use core::{fmt, str::FromStr};
+
+/// A four byte ECU, application or context ID.
+///
+/// The bytes are kept as they are on the wire, padded with trailing nulls.
+/// They are usually ASCII; anything else is shown with replacement characters
+/// and usually means the message was misframed.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Id4([u8; 4]);
+
+impl Id4 {
+    pub const fn new(bytes: [u8; 4]) -> Self {
+        Self(bytes)
+    }
+
+    /// The raw field, including padding.
+    pub const fn to_bytes(self) -> [u8; 4] {
+        self.0
+    }
+
+    /// The ID without its padding.
+    pub fn as_bytes(&self) -> &[u8] {
+        strip_null(&self.0)
+    }
+
+    /// The ID without its padding, or `None` if it is not pure ASCII.
+    pub fn as_str(&self) -> Option<&str> {
+        let bytes = self.as_bytes();
+        bytes
+            .is_ascii()
+            .then(|| core::str::from_utf8(bytes).expect("ASCII is valid UTF-8"))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.as_bytes().is_empty()
+    }
+}
+
+impl From<[u8; 4]> for Id4 {
+    fn from(bytes: [u8; 4]) -> Self {
+        Self(bytes)
+    }
+}
+
+/// Error returned when parsing an [`Id4`] from a string that is longer than
+/// four bytes or not ASCII.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseIdError;
+
+impl fmt::Display for ParseIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("IDs are at most 4 ASCII characters")
+    }
+}
+
+impl core::error::Error for ParseIdError {}
+
+impl FromStr for Id4 {
+    type Err = ParseIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() > 4 || !s.is_ascii() {
+            return Err(ParseIdError);
+        }
+
+        let mut bytes = [0; 4];
+        bytes[..s.len()].copy_from_slice(s.as_bytes());
+
+        Ok(Self(bytes))
+    }
+}
+
+/// Writes the ID without its padding, honoring width and alignment.
+impl fmt::Display for Id4 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(id) = self.as_str() {
+            return f.pad(id);
+        }
+
+        let mut id = [0; 16];
+        let mut len = 0;
+        for &byte in self.as_bytes() {
+            let c = if byte.is_ascii() {
+                char::from(byte)
+            } else {
+                char::REPLACEMENT_CHARACTER
+            };
+            len += c.encode_utf8(&mut id[len..]).len();
+        }
+
+        f.pad(core::str::from_utf8(&id[..len]).expect("encoded from chars"))
+    }
+}
+
+impl fmt::Debug for Id4 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Id4(\"{}\")", self.as_bytes().escape_ascii())
+    }
+}
+
+impl PartialEq<str> for Id4 {
+    fn eq(&self, other: &str) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl PartialEq<&str> for Id4 {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Id4 {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    #[test]
+    fn empty_id() {
+        let id = Id4::new([0; 4]);
+
+        assert!(id.is_empty());
+        assert_eq!(id.as_bytes(), b"");
+        assert_eq!(id.as_str(), Some(""));
+        assert_eq!(format!("{id}"), "");
+        assert_eq!("".parse(), Ok(id));
+    }
+
+    #[test]
+    fn one_byte_id() {
+        let id = Id4::new(*b"E\0\0\0");
+
+        assert_eq!(id.as_str(), Some("E"));
+        assert_eq!(format!("{id:>3}|"), "  E|");
+        assert_eq!("E".parse(), Ok(id));
+    }
+
+    #[test]
+    fn four_byte_id_is_not_truncated() {
+        let id = Id4::new(*b"ECU1");
+
+        assert_eq!(id.as_str(), Some("ECU1"));
+        assert_eq!(format!("{id}"), "ECU1");
+        assert_eq!("ECU1".parse(), Ok(id));
+        assert_eq!("ECU12".parse::<Id4>(), Err(ParseIdError));
+    }
+
+    #[test]
+    fn only_trailing_nulls_are_padding() {
+        let id = Id4::new(*b"E\0U\0");
+
+        assert_eq!(id.as_bytes(), b"E\0U");
+        assert_eq!(strip_null(b"\0\0"), b"");
+        assert_eq!(strip_null(b""), b"");
+    }
+}
@@ -0,0 +1,223 @@
+#[cfg(any(feature = "std", feature = "chrono"))]
+use crate::parse_message;
+#[cfg(feature = "chrono")]
+use crate::Message;
+use crate::{parse_storage_header, scan::message_offsets, StorageTime};
+use alloc::vec::Vec;
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// Offsets and storage timestamps of the messages of a buffer, for random
+/// access without rescanning.
+///
+/// The offsets point at storage headers and can be passed to
+/// [`parse_message`](crate::parse_message) by slicing the indexed buffer.
+#[derive(Debug, Clone, Default)]
+pub struct Index {
+    entries: Vec<IndexEntry>,
+    /// Whether the timestamps never decrease, so that they can be searched.
+    monotonic: bool,
+}
+
+#[derive(Debug, Clone)]
+struct IndexEntry {
+    offset: usize,
+    timestamp: StorageTime,
+}
+
+/// Indexes the messages found by [`message_offsets`], skipping those whose
+/// storage header cannot be parsed.
+pub fn build_index(data: &[u8]) -> Index {
+    let entries = message_offsets(data)
+        .into_iter()
+        .filter_map(|offset| {
+            let (storage_header, _) = parse_storage_header(&data[offset..]).ok()?;
+
+            Some(IndexEntry {
+                offset,
+                timestamp: storage_header.timestamp,
+            })
+        })
+        .collect::<Vec<_>>();
+    let monotonic = entries.is_sorted_by_key(|it| it.timestamp);
+
+    Index { entries, monotonic }
+}
+
+impl Index {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether the storage timestamps never decrease, as in well-formed
+    /// files.
+    pub fn is_monotonic(&self) -> bool {
+        self.monotonic
+    }
+
+    /// Byte offset of the `n`th message.
+    pub fn message_at(&self, n: usize) -> Option<usize> {
+        self.entries.get(n).map(|it| it.offset)
+    }
+
+    /// Byte offset of the first message stored at or after `ts`.
+    ///
+    /// This is a binary search, so the result is only meaningful if the index
+    /// [`is_monotonic`](Self::is_monotonic).
+    pub fn first_after(&self, ts: StorageTime) -> Option<usize> {
+        let n = self.entries.partition_point(|it| it.timestamp < ts);
+        self.message_at(n)
+    }
+
+    /// The messages of `data`, the buffer this index was built from, with a
+    /// storage timestamp in `start..=end`.
+    ///
+    /// If the storage timestamps never decrease, the first message in range
+    /// is found by a binary search and iteration stops at the last one.
+    /// Otherwise every indexed timestamp is compared. Only messages in range
+    /// are parsed, and those that cannot be are skipped.
+    #[cfg(feature = "chrono")]
+    pub fn messages_in_range<'s, 'a: 's>(
+        &'s self,
+        data: &'a [u8],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> impl Iterator<Item = Message<'a>> + 's {
+        let entries = if self.is_monotonic() {
+            let first = self
+                .entries
+                .partition_point(|it| it.timestamp.to_datetime() < Some(start));
+            let len =
+                self.entries[first..].partition_point(|it| it.timestamp.to_datetime() <= Some(end));
+            &self.entries[first..first + len]
+        } else {
+            &self.entries[..]
+        };
+
+        entries
+            .iter()
+            .filter(move |it| {
+                it.timestamp
+                    .to_datetime()
+                    .is_some_and(|it| start <= it && it <= end)
+            })
+            .filter_map(move |it| Some(parse_message(data.get(it.offset..)?).ok()?.0))
+    }
+}
+
+/// Offsets of the messages found by [`message_offsets`] per session ID, in
+/// buffer order.
+///
+/// Messages without a session ID, or that cannot be parsed, are left out.
+#[cfg(feature = "std")]
+pub fn group_by_session(data: &[u8]) -> HashMap<u32, Vec<usize>> {
+    let mut sessions = HashMap::<_, Vec<_>>::new();
+
+    for offset in message_offsets(data) {
+        let Ok((message, _)) = parse_message(&data[offset..]) else {
+            continue;
+        };
+
+        if let Some(session_id) = message.extensions.session_id {
+            sessions.entry(session_id).or_default().push(offset);
+        }
+    }
+
+    sessions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{capture_bytes, info_message};
+
+    /// A capture with one message per entry of `messages`, each given as the
+    /// storage timestamp seconds and an optional session ID. The counters
+    /// number the messages.
+    fn capture(messages: &[(u32, Option<u32>)]) -> Vec<u8> {
+        capture_bytes(messages.iter().enumerate().map(|(i, &(secs, session_id))| {
+            let message = info_message(i as u8).timestamp(StorageTime { secs, micros: 0 });
+            match session_id {
+                Some(session_id) => message.session_id(session_id),
+                None => message,
+            }
+        }))
+    }
+
+    #[test]
+    fn first_after_includes_the_boundary() {
+        let data = capture(&[(10, None), (20, None), (20, None), (30, None)]);
+        let len = data.len() / 4;
+        let index = build_index(&data);
+        let at = |secs| index.first_after(StorageTime { secs, micros: 0 });
+
+        assert_eq!(index.len(), 4);
+        assert!(index.is_monotonic());
+        assert!(!build_index(&capture(&[(20, None), (10, None)])).is_monotonic());
+        assert_eq!(at(0), Some(0));
+        assert_eq!(at(20), Some(len));
+        assert_eq!(at(21), Some(3 * len));
+        assert_eq!(at(30), Some(3 * len));
+        assert_eq!(at(31), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn group_by_session_with_interleaved_sessions() {
+        let data = capture(&[
+            (1, Some(7)),
+            (2, Some(9)),
+            (3, Some(7)),
+            (4, None),
+            (5, Some(9)),
+        ]);
+        let offsets = message_offsets(&data);
+
+        assert_eq!(
+            group_by_session(&data),
+            HashMap::from([
+                (7, vec![offsets[0], offsets[2]]),
+                (9, vec![offsets[1], offsets[4]])
+            ])
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    fn in_range(data: &[u8], start: i64, end: i64) -> Vec<u8> {
+        let time = |secs| DateTime::from_timestamp(secs, 0).unwrap();
+
+        build_index(data)
+            .messages_in_range(data, time(start), time(end))
+            .map(|it| it.standard_header.mcnt)
+            .collect()
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn messages_in_range_includes_both_boundaries() {
+        let data = capture(&[(10, None), (20, None), (20, None), (30, None), (40, None)]);
+
+        assert_eq!(in_range(&data, 20, 30), [1, 2, 3]);
+        assert_eq!(in_range(&data, 10, 10), [0]);
+        assert_eq!(in_range(&data, 40, 100), [4]);
+        assert!(in_range(&data, 21, 29).is_empty());
+        assert!(in_range(&data, 0, 9).is_empty());
+        assert!(in_range(&data, 41, 50).is_empty());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn messages_in_range_scans_non_monotonic_captures() {
+        let data = capture(&[(30, None), (10, None), (20, None), (40, None), (20, None)]);
+
+        assert_eq!(in_range(&data, 20, 30), [0, 2, 4]);
+        assert_eq!(in_range(&data, 10, 20), [1, 2, 4]);
+        assert_eq!(in_range(&data, 40, 40), [3]);
+    }
+}
@@ -0,0 +1,329 @@
+use crate::{
+    parse_message_with,
+    scan::{find_magic, message_len, peek_extended_header},
+    DltParseError, ExtendedHeader, Message, OwnedMessage, ParseOptions,
+};
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+/// Iterator over the consecutive messages of a buffer.
+///
+/// Error offsets are relative to the start of the whole buffer. After the
+/// first error the iterator is exhausted, unless resync is enabled.
+#[derive(Debug, Clone)]
+pub struct MessageIter<'a> {
+    data: &'a [u8],
+    offset: usize,
+    opts: ParseOptions,
+    skipped: usize,
+    count_hint: usize,
+}
+
+pub fn messages(data: &[u8]) -> MessageIter<'_> {
+    messages_with(data, ParseOptions::default())
+}
+
+/// Like [`messages`], parsing every message with `opts`.
+pub fn messages_with(data: &[u8], opts: ParseOptions) -> MessageIter<'_> {
+    MessageIter {
+        data,
+        offset: 0,
+        opts,
+        skipped: 0,
+        count_hint: 0,
+    }
+}
+
+/// A buffer of storage framed messages together with the options to parse
+/// them with, so that `for message in &file` iterates over them.
+///
+/// ```
+/// use dlt_convert::{write_message, DltFile, LogTypeInfo, MessageBuilder};
+///
+/// let mut data = Vec::new();
+/// for counter in 0..3 {
+///     let message = MessageBuilder::log(LogTypeInfo::Info)
+///         .app("APP")
+///         .ctx("CTX")
+///         .counter(counter)
+///         .arg_str("hello")
+///         .build();
+///     write_message(&message.as_message(), &mut data);
+/// }
+///
+/// let file = DltFile::new(&data);
+/// let mut counters = Vec::new();
+/// for msg in &file {
+///     counters.push(msg?.standard_header.mcnt);
+/// }
+/// assert_eq!(counters, [0, 1, 2]);
+/// # Ok::<(), dlt_convert::DltParseError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct DltFile<'a> {
+    data: &'a [u8],
+    opts: ParseOptions,
+}
+
+impl<'a> DltFile<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self::with_options(data, ParseOptions::default())
+    }
+
+    pub fn with_options(data: &'a [u8], opts: ParseOptions) -> Self {
+        Self { data, opts }
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Iterates over the messages, like [`messages_with`].
+    pub fn messages(&self) -> MessageIter<'a> {
+        messages_with(self.data, self.opts.clone())
+    }
+}
+
+impl<'a> IntoIterator for &DltFile<'a> {
+    type Item = Result<Message<'a>, DltParseError>;
+    type IntoIter = MessageIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.messages()
+    }
+}
+
+impl<'a> IntoIterator for DltFile<'a> {
+    type Item = Result<Message<'a>, DltParseError>;
+    type IntoIter = MessageIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        messages_with(self.data, self.opts)
+    }
+}
+
+/// Checks that `data` consists of nothing but well formed messages.
+///
+/// Returns the number of messages, or the start offset of the first message
+/// that failed to parse together with its error.
+pub fn validate(data: &[u8]) -> Result<usize, (usize, DltParseError)> {
+    let mut iter = messages(data);
+    let mut count = 0;
+
+    loop {
+        let offset = iter.offset;
+        match iter.next() {
+            None => return Ok(count),
+            Some(Ok(_)) => count += 1,
+            Some(Err(err)) => return Err((offset, err)),
+        }
+    }
+}
+
+/// Parses as many messages of a possibly damaged capture as possible,
+/// resynchronizing on the next storage header magic after every error.
+///
+/// Returns the messages that parsed, and for every failure the offset at which
+/// the failed message started together with its error.
+pub fn parse_all_lossy(data: &[u8]) -> (Vec<OwnedMessage>, Vec<(usize, DltParseError)>) {
+    let mut iter = messages(data).with_resync(true);
+    let mut parsed = Vec::new();
+    let mut errors = Vec::new();
+
+    loop {
+        let offset = iter.offset;
+        match iter.next() {
+            None => return (parsed, errors),
+            Some(Ok(message)) => parsed.push(message.into_owned()),
+            Some(Err(err)) => errors.push((offset, err)),
+        }
+    }
+}
+
+impl MessageIter<'_> {
+    /// When enabled, a parse error is still yielded but iteration then
+    /// continues at the next storage header magic instead of stopping.
+    ///
+    /// Without storage headers there is nothing to resynchronize on, so
+    /// iteration always stops at the first error.
+    pub fn with_resync(mut self, resync: bool) -> Self {
+        self.opts.resync = resync;
+        self
+    }
+
+    /// Skips the next message without parsing it in full if it is complete
+    /// and `reject` returns `true` for its extended header. Other headers are
+    /// not validated. Returns whether a message was skipped.
+    ///
+    /// Only storage framed messages can be skipped this way.
+    pub(crate) fn skip_if(&mut self, reject: impl FnOnce(Option<&ExtendedHeader>) -> bool) -> bool {
+        if !self.opts.storage_header {
+            return false;
+        }
+
+        let Some(len) =
+            message_len(self.data, &self.opts.storage_magic).filter(|&len| len <= self.data.len())
+        else {
+            return false;
+        };
+
+        let rejected =
+            peek_extended_header(&self.data[..len]).is_some_and(|it| reject(it.as_ref()));
+
+        if rejected {
+            self.data = &self.data[len..];
+            self.offset += len;
+            self.count_hint = self.count_hint.saturating_sub(1);
+        }
+
+        rejected
+    }
+
+    /// Sets the number of items to expect, such as the number of offsets found
+    /// by [`message_offsets`](crate::message_offsets), see
+    /// [`capacity_hint`](Self::capacity_hint).
+    ///
+    /// The hint is not trusted, so it does not change `size_hint`.
+    pub fn with_count_hint(mut self, count: usize) -> Self {
+        self.count_hint = count;
+        self
+    }
+
+    /// The number of items still expected according to
+    /// [`with_count_hint`](Self::with_count_hint), meant for preallocating.
+    /// Iteration may end before that many items if the hint was too high.
+    pub fn capacity_hint(&self) -> usize {
+        self.count_hint
+    }
+
+    /// Total number of bytes skipped while resynchronizing so far.
+    ///
+    /// Each message spans exactly the length declared in its standard header,
+    /// so these are the only bytes of the buffer not covered by a message.
+    /// Comparing the count before and after a call to `next` gives the bytes
+    /// skipped in front of that message.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+}
+
+impl<'a> Iterator for MessageIter<'a> {
+    type Item = Result<Message<'a>, DltParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        self.count_hint = self.count_hint.saturating_sub(1);
+
+        match parse_message_with(self.data, &self.opts) {
+            Ok((message, rest)) => {
+                self.offset += self.data.len() - rest.len();
+                self.data = rest;
+                Some(Ok(message))
+            }
+            Err(err) => {
+                let err = err.offset_by(self.offset);
+                let skip = if self.opts.resync && self.opts.storage_header {
+                    let skip = find_magic(&self.data[1..], &self.opts.storage_magic)
+                        .map_or(self.data.len(), |it| 1 + it);
+                    self.skipped += skip;
+                    skip
+                } else {
+                    self.data.len()
+                };
+
+                self.offset += skip;
+                self.data = &self.data[skip..];
+                Some(Err(err))
+            }
+        }
+    }
+
+    /// Every item consumes at least one byte, and there is another item as
+    /// long as any bytes are left.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::from(!self.data.is_empty()), Some(self.data.len()))
+    }
+}
+
+impl FusedIterator for MessageIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{corrupt_version, numbered_capture};
+
+    /// Three messages with the counters 0, 1 and 2, and the length of each.
+    fn capture() -> (Vec<u8>, usize) {
+        let data = numbered_capture(3);
+        let len = data.len() / 3;
+        (data, len)
+    }
+
+    #[test]
+    fn resync_skips_a_corrupt_middle_message() {
+        let (mut data, len) = capture();
+        data[len..len + 8].fill(0xff);
+
+        let mut iter = messages(&data).with_resync(true);
+
+        assert_eq!(iter.next().unwrap().unwrap().standard_header.mcnt, 0);
+        assert!(matches!(
+            iter.next(),
+            Some(Err(DltParseError::BadStorageMagic { offset, .. })) if offset == len
+        ));
+        assert_eq!(iter.next().unwrap().unwrap().standard_header.mcnt, 2);
+        assert!(iter.next().is_none());
+        assert_eq!(iter.skipped(), len);
+    }
+
+    #[test]
+    fn without_resync_iteration_stops_at_the_corrupt_message() {
+        let (mut data, len) = capture();
+        data[len..len + 8].fill(0xff);
+
+        let results: Vec<_> = messages(&data).collect();
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn parse_all_lossy_keeps_the_good_messages() {
+        let (data, len) = capture();
+        let mut corrupt = data[..len].to_vec();
+        corrupt_version(&mut corrupt, 0);
+        let data = [&data[..len], &corrupt, &data[len..]].concat();
+
+        let (parsed, errors) = parse_all_lossy(&data);
+
+        let counters: Vec<_> = parsed
+            .iter()
+            .map(|message| message.as_message().standard_header.mcnt)
+            .collect();
+        assert_eq!(counters, [0, 1, 2]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, len);
+        assert!(matches!(
+            errors[0].1,
+            DltParseError::UnsupportedVersion { .. }
+        ));
+    }
+
+    #[test]
+    fn count_hint_is_not_a_lower_bound() {
+        let (mut data, len) = capture();
+        data[len..len + 8].fill(0xff);
+
+        let mut iter = messages(&data).with_count_hint(3);
+        assert_eq!(iter.capacity_hint(), 3);
+        assert_eq!(iter.size_hint(), (1, Some(data.len())));
+
+        assert_eq!(iter.by_ref().count(), 2);
+        assert_eq!(iter.capacity_hint(), 1);
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+    }
+}
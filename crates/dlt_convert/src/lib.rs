@@ -1,91 +1,487 @@
-use chrono::{DateTime, Utc};
-use std::borrow::Cow;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{borrow::Cow, string::ToString, vec::Vec};
+use core::{cmp::Ordering, time::Duration};
+
+mod builder;
+mod catalog;
+mod control;
+mod counter;
+mod decoder;
+mod dedup;
+#[cfg(feature = "tracing")]
+mod emit;
+mod encode;
+mod error;
+#[cfg(feature = "csv")]
+mod export;
+#[cfg(feature = "fibex")]
+mod fibex;
+#[cfg(feature = "std")]
+mod file;
+mod filter;
+mod format;
+mod id;
+mod index;
+mod iter;
+mod merge;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "net")]
+mod net;
+mod options;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "protobuf")]
+mod proto;
+mod scan;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "std")]
+mod summary;
+#[cfg(any(test, feature = "test-util"))]
+mod test_util;
+mod verbose;
+#[cfg(feature = "std")]
+mod writer;
+
+pub use builder::MessageBuilder;
+pub use catalog::{ArgumentType, MemoryCatalog, MessageCatalog, MessageDef};
+pub use control::{
+    parse_control_payload, parse_get_log_info, AppLogInfo, ContextLogInfo, ControlMessage,
+    GetLogInfoResponse, ResponseStatus, ServiceId,
+};
+pub use counter::CounterTracker;
+pub use decoder::PayloadDecoder;
+pub use dedup::{dedup_consecutive, DedupKey, DedupMessages};
+#[cfg(feature = "tracing")]
+pub use emit::emit_tracing;
+pub use encode::write_message;
+pub use error::{ArgumentError, DltParseError};
+#[cfg(feature = "csv")]
+pub use export::write_csv;
+#[cfg(feature = "fibex")]
+pub use fibex::{load_fibex, FibexCatalog, FibexError};
+#[cfg(feature = "gzip")]
+pub use file::parse_gz_file;
+#[cfg(feature = "std")]
+pub use file::{follow, parse_file, parse_reader};
+pub use filter::{filter_file, filter_file_with, FilteredMessages, MessageFilter};
+pub use id::{Id4, ParseIdError};
+#[cfg(feature = "std")]
+pub use index::group_by_session;
+pub use index::{build_index, Index};
+pub use iter::{messages, messages_with, parse_all_lossy, validate, DltFile, MessageIter};
+#[cfg(feature = "mmap")]
+pub use memmap2::Mmap;
+pub use merge::{merge, MergedMessages};
+#[cfg(feature = "mmap")]
+pub use mmap::open_mmap;
+#[cfg(feature = "net")]
+pub use net::{connect_tcp, TcpMessageStream, DEFAULT_TCP_PORT};
+pub use options::ParseOptions;
+#[cfg(feature = "rayon")]
+pub use par::par_messages;
+#[cfg(feature = "protobuf")]
+pub use proto::{
+    argument_proto, to_proto, ArgumentProto, DltMessageProto, ExtendedHeaderProto, ExtensionsProto,
+    StandardHeaderProto, StorageHeaderProto,
+};
+pub use scan::{
+    message_len_if_complete, message_len_if_complete_no_storage, message_len_if_complete_with,
+    message_offsets, message_offsets_with, payload_slices, payload_slices_with, MessageAvail,
+};
+#[cfg(feature = "std")]
+pub use stream::StreamParser;
+#[cfg(all(feature = "std", feature = "chrono"))]
+pub use summary::throughput_by_app;
+#[cfg(feature = "std")]
+pub use summary::{summarize, summarize_filtered, Summary};
+#[cfg(any(test, feature = "test-util"))]
+pub use test_util::{
+    capture_bytes, corrupt_version, extended_header_bytes, info_message, numbered_capture,
+    standard_header_bytes, storage_header_bytes,
+};
+pub use verbose::{
+    parse_verbose_payload, parse_verbose_payload_until_end, verbose_arguments, Argument,
+    ArgumentIter, StringCoding,
+};
+#[cfg(feature = "std")]
+pub use writer::DltWriter;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Message<'a> {
-    pub storage_header: StorageHeader<'a>,
+    pub storage_header: Option<StorageHeader>,
     pub standard_header: StandardHeader,
-    pub extended_header: Option<ExtendedHeader<'a>>,
+    pub extensions: StandardExtensions,
+    pub extended_header: Option<ExtendedHeader>,
+    /// Everything after the headers, up to the message length. Empty for
+    /// header-only messages.
+    ///
+    /// These are the raw bytes; only the text accessors like
+    /// [`Message::payload_text_trimmed`] drop trailing null bytes.
     pub payload: &'a [u8],
+    /// The payload byte order set with
+    /// [`ParseOptions::msb_first`](crate::ParseOptions::msb_first), which
+    /// takes precedence over the MSBF flag of `htyp`. `None` to follow the
+    /// flag.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub msb_first: Option<bool>,
 }
 
-pub fn parse_message(data: &[u8]) -> Option<(Message, &[u8])> {
-    let start = data;
+impl Message<'_> {
+    /// The message type from the extended header, if there is one.
+    pub fn message_kind(&self) -> Option<&MessageInfo> {
+        self.extended_header.as_ref().map(|it| &it.message_type)
+    }
+
+    /// The log level, or `None` if this is not a log message.
+    pub fn log_level(&self) -> Option<LogTypeInfo> {
+        match self.message_kind()? {
+            MessageInfo::Log { level } => Some(*level),
+            _ => None,
+        }
+    }
+
+    /// Whether the payload is encoded big-endian, as declared by the MSBF flag
+    /// of this message unless overridden by [`Message::msb_first`]. The
+    /// argument and control payload decoders on `Message` use it on their own.
+    pub fn is_big_endian(&self) -> bool {
+        self.msb_first
+            .unwrap_or_else(|| self.standard_header.msb_first())
+    }
+
+    /// The service ID and data of a control message, or `None` for other
+    /// messages.
+    pub fn control_message(&self) -> Option<ControlMessage<'_>> {
+        let MessageInfo::Control { kind } = self.message_kind()? else {
+            return None;
+        };
+
+        let mut message = parse_control_payload(self.payload, self.is_big_endian())?;
+        message.kind = Some(*kind);
+        Some(message)
+    }
+
+    /// The number of arguments announced by a verbose message, or `None` for
+    /// non-verbose messages.
+    pub fn argument_count(&self) -> Option<u8> {
+        self.extended_header
+            .as_ref()
+            .filter(|it| it.verbose)
+            .map(|it| it.noar)
+    }
+
+    pub fn is_control(&self) -> bool {
+        matches!(self.message_kind(), Some(MessageInfo::Control { .. }))
+    }
+
+    /// The ECU ID from the standard header extensions, falling back to the
+    /// one of the storage header.
+    pub(crate) fn ecu(&self) -> Option<Id4> {
+        self.extensions
+            .ecu_id
+            .or(self.storage_header.as_ref().map(|it| it.ecu))
+    }
+
+    /// The leading message ID of a non-verbose payload, or `None` for verbose
+    /// messages.
+    pub fn non_verbose_message_id(&self) -> Option<u32> {
+        if self.extended_header.as_ref().is_some_and(|it| it.verbose) {
+            return None;
+        }
+
+        let id_bytes = self.payload.first_chunk::<4>()?;
+
+        Some(if self.is_big_endian() {
+            u32::from_be_bytes(*id_bytes)
+        } else {
+            u32::from_le_bytes(*id_bytes)
+        })
+    }
+
+    /// Number of bytes the message occupied in its buffer, including the
+    /// storage header if there is one.
+    pub fn total_len(&self) -> usize {
+        let storage_len = match self.storage_header {
+            Some(_) => STORAGE_HEADER_LEN,
+            None => 0,
+        };
+
+        storage_len + self.standard_header.len
+    }
+
+    pub fn into_owned(self) -> OwnedMessage {
+        OwnedMessage {
+            storage_header: self.storage_header,
+            standard_header: self.standard_header,
+            extensions: self.extensions,
+            extended_header: self.extended_header,
+            payload: self.payload.to_vec(),
+            msb_first: self.msb_first,
+        }
+    }
+
+    pub fn to_owned(&self) -> OwnedMessage {
+        OwnedMessage {
+            storage_header: self.storage_header.clone(),
+            standard_header: self.standard_header.clone(),
+            extensions: self.extensions.clone(),
+            extended_header: self.extended_header.clone(),
+            payload: self.payload.to_vec(),
+            msb_first: self.msb_first,
+        }
+    }
+}
 
-    let (storage_header, data) = parse_storage_header(data)?;
+/// A [`Message`] that owns all of its data and no longer borrows the input buffer.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct OwnedMessage {
+    pub storage_header: Option<StorageHeader>,
+    pub standard_header: StandardHeader,
+    pub extensions: StandardExtensions,
+    pub extended_header: Option<ExtendedHeader>,
+    pub payload: Vec<u8>,
+    /// See [`Message::msb_first`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub msb_first: Option<bool>,
+}
 
-    if storage_header.pattern != &[0x44, 0x4c, 0x54, 0x01] {
-        return None;
+impl OwnedMessage {
+    /// Borrows the message, for example to pass it to [`write_message`].
+    pub fn as_message(&self) -> Message<'_> {
+        Message {
+            storage_header: self.storage_header.clone(),
+            standard_header: self.standard_header.clone(),
+            extensions: self.extensions.clone(),
+            extended_header: self.extended_header.clone(),
+            payload: &self.payload,
+            msb_first: self.msb_first,
+        }
     }
+}
 
-    let (standard_header, data) = parse_standard_header(data)?;
+pub(crate) const STORAGE_MAGIC: [u8; 4] = *b"DLT\x01";
+pub(crate) const STORAGE_HEADER_LEN: usize = 16;
 
+/// Parses the storage framed message at the start of `data` and returns it
+/// together with the bytes following it.
+///
+/// `data` may start anywhere in a larger buffer, such as `&buf[offset..]` with
+/// an offset from [`message_offsets`] or an [`Index`]. Error offsets are then
+/// relative to `offset` rather than to the start of `buf`.
+pub fn parse_message(data: &[u8]) -> Result<(Message<'_>, &[u8]), DltParseError> {
+    parse_message_with(data, &ParseOptions::default())
+}
 
-    let msb_first = standard_header.htyp & 0x02 != 0;
-    if msb_first {
-        return None;
+/// Like [`parse_message`], with the behavior adjusted by `opts`.
+pub fn parse_message_with<'a>(
+    data: &'a [u8],
+    opts: &ParseOptions,
+) -> Result<(Message<'a>, &'a [u8]), DltParseError> {
+    if !opts.storage_header {
+        return parse_message_body(data, data, None, opts);
     }
 
-    let with_ecu_id = standard_header.htyp & 0x04 != 0;
-    let with_session_id = standard_header.htyp & 0x08 != 0;
-    let with_timestamp = standard_header.htyp & 0x10 != 0;
+    let start = data;
+
+    let (mut storage_header, data) = parse_storage_header(data)?;
+
+    if storage_header.pattern != opts.storage_magic {
+        return Err(DltParseError::BadStorageMagic {
+            offset: 0,
+            found: storage_header.pattern,
+        });
+    }
+
+    let micros = storage_header.timestamp.micros;
+    if micros >= 1_000_000 {
+        if opts.strict_micros {
+            return Err(DltParseError::InvalidMicros { offset: 8, micros });
+        }
+
+        storage_header.timestamp.micros = 999_999;
+    }
+
+    parse_message_body(start, data, Some(storage_header), opts)
+}
+
+/// Parses a message as sent over the network, which starts directly at the
+/// standard header without a storage header.
+pub fn parse_message_no_storage(data: &[u8]) -> Result<(Message<'_>, &[u8]), DltParseError> {
+    parse_message_with(data, &ParseOptions::new().storage_header(false))
+}
+
+fn parse_message_body<'a>(
+    start: &'a [u8],
+    data: &'a [u8],
+    storage_header: Option<StorageHeader>,
+    opts: &ParseOptions,
+) -> Result<(Message<'a>, &'a [u8]), DltParseError> {
+    let standard_start = data;
+    let standard_offset = start.len() - data.len();
+    let (standard_header, data) =
+        parse_standard_header_with(data, opts).map_err(|e| e.offset_by(standard_offset))?;
 
-    let (_, data) = parse_extensions(with_ecu_id, with_session_id, with_timestamp)(data)?;
+    let header_type = standard_header.header_type();
 
-    let with_extended_header = standard_header.htyp & 0x01 != 0;
+    let (extensions, data) = parse_extensions(
+        header_type.with_ecu_id,
+        header_type.with_session_id,
+        header_type.with_timestamp,
+    )(data)
+    .map_err(|e| e.offset_by(start.len() - data.len()))?;
 
-    let (extended_header, data) = if with_extended_header {
-        parse_extended_header(data).map(|(it, data)| (Some(it), data))?
+    let (extended_header, data) = if header_type.use_extended_header {
+        parse_extended_header(data)
+            .map(|(it, data)| (Some(it), data))
+            .map_err(|e| e.offset_by(start.len() - data.len()))?
     } else {
         (None, data)
     };
 
-    let (_, data) = data.split_at(6);
+    let parsed_bytes = standard_start.len() - data.len();
 
-    let parsed_bytes = data.as_ptr() as usize - start.as_ptr() as usize;
-
-    let rest_bytes = standard_header.len - parsed_bytes;
+    let Some(rest_bytes) = standard_header.len.checked_sub(parsed_bytes) else {
+        return Err(DltParseError::LengthUnderflow {
+            offset: standard_offset,
+            len: standard_header.len,
+            headers: parsed_bytes,
+        });
+    };
 
-    let (payload, data) = data.split_at(rest_bytes);
+    let Some((payload, data)) = data.split_at_checked(rest_bytes) else {
+        return Err(DltParseError::UnexpectedEof {
+            offset: start.len(),
+        });
+    };
 
-    Some((
+    Ok((
         Message {
             standard_header,
             storage_header,
+            extensions,
             extended_header,
             payload,
+            msb_first: opts.msb_first,
         },
         data,
     ))
 }
 
-#[derive(Debug)]
-pub struct StorageHeader<'a> {
-    pub pattern: &'a [u8; 4],
-    pub timestamp: DateTime<Utc>,
-    pub ecu: Cow<'a, str>,
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StorageHeader {
+    pub pattern: [u8; 4],
+    pub timestamp: StorageTime,
+    pub ecu: Id4,
 }
 
-pub fn parse_storage_header(data: &[u8]) -> Option<(StorageHeader, &[u8])> {
-    let (pattern_bytes, data) = data.split_first_chunk::<4>()?;
-    let (seconds_bytes, data) = data.split_first_chunk::<4>()?;
-    let (microseconds_bytes, data) = data.split_first_chunk::<4>()?;
-    let (ecu_bytes, data) = data.split_first_chunk::<4>()?;
+/// Time at which the logger stored a message, as seconds and microseconds
+/// since the Unix epoch.
+///
+/// Parsed timestamps have `micros` below one million, see
+/// [`ParseOptions::strict_micros`]. Values are ordered by the point in time
+/// they describe, so larger microseconds set by hand are carried over into the
+/// seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StorageTime {
+    pub secs: u32,
+    pub micros: u32,
+}
 
-    let seconds = u32::from_le_bytes(*seconds_bytes);
-    let microseconds = i32::from_le_bytes(*microseconds_bytes);
+impl StorageTime {
+    /// Converts to a `chrono` timestamp, or `None` if out of range.
+    #[cfg(feature = "chrono")]
+    pub fn to_datetime(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let (secs, micros) = self.normalized();
+        chrono::DateTime::from_timestamp(secs, micros * 1000)
+    }
 
-    let timestamp = DateTime::from_timestamp(
-        (seconds + microseconds as u32 / 1000000) as i64,
-        ((microseconds % 1000000) * 1000) as u32,
-    )
-    .unwrap();
+    /// Converts to a `chrono` timestamp in `tz`, which may also be a named
+    /// time zone from `chrono-tz`, or `None` if out of range.
+    #[cfg(feature = "chrono")]
+    pub fn to_datetime_in<Tz: chrono::TimeZone>(&self, tz: &Tz) -> Option<chrono::DateTime<Tz>> {
+        Some(self.to_datetime()?.with_timezone(tz))
+    }
+
+    /// The local time at `offset` seconds east of UTC, which formats like the
+    /// timestamp itself.
+    pub fn with_offset(self, offset: i32) -> OffsetTime {
+        OffsetTime { time: self, offset }
+    }
+
+    /// Whole seconds and the remaining microseconds in `0..1_000_000`.
+    pub(crate) fn normalized(&self) -> (i64, u32) {
+        (
+            i64::from(self.secs) + i64::from(self.micros / 1_000_000),
+            self.micros % 1_000_000,
+        )
+    }
+}
 
-    let ecu = String::from_utf8_lossy(strip_null(ecu_bytes));
+/// A [`StorageTime`] shown at a fixed UTC offset, see
+/// [`StorageTime::with_offset`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OffsetTime {
+    pub time: StorageTime,
+    /// Seconds east of UTC.
+    pub offset: i32,
+}
 
-    Some((
+impl PartialOrd for StorageTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for StorageTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.normalized()
+            .cmp(&other.normalized())
+            .then(self.micros.cmp(&other.micros))
+    }
+}
+
+impl StorageHeader {
+    /// The ECU ID as text, with bytes that are not ASCII replaced by
+    /// U+FFFD. Only allocates for such IDs.
+    pub fn ecu(&self) -> Cow<'_, str> {
+        match self.ecu.as_str() {
+            Some(ecu) => Cow::Borrowed(ecu),
+            None => Cow::Owned(self.ecu.to_string()),
+        }
+    }
+
+    /// The ECU ID, or `None` if it is not pure ASCII.
+    pub fn ecu_ascii(&self) -> Option<&str> {
+        self.ecu.as_str()
+    }
+}
+
+pub fn parse_storage_header(data: &[u8]) -> Result<(StorageHeader, &[u8]), DltParseError> {
+    let start = data;
+
+    let (pattern_bytes, data) = take::<4>(start, data)?;
+    let (seconds_bytes, data) = take::<4>(start, data)?;
+    let (microseconds_bytes, data) = take::<4>(start, data)?;
+    let (ecu_bytes, data) = take::<4>(start, data)?;
+
+    let timestamp = StorageTime {
+        secs: u32::from_le_bytes(*seconds_bytes),
+        micros: u32::from_le_bytes(*microseconds_bytes),
+    };
+
+    let ecu = Id4::new(*ecu_bytes);
+
+    Ok((
         StorageHeader {
-            pattern: pattern_bytes,
+            pattern: *pattern_bytes,
             timestamp,
             ecu,
         },
@@ -93,21 +489,91 @@ pub fn parse_storage_header(data: &[u8]) -> Option<(StorageHeader, &[u8])> {
     ))
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct StandardHeader {
     pub htyp: u8,
     pub mcnt: u8,
+    /// Length of the message starting at the standard header.
     pub len: usize,
 }
 
-pub fn parse_standard_header(data: &[u8]) -> Option<(StandardHeader, &[u8])> {
-    let ([htyp], data) = data.split_first_chunk::<1>()?;
-    let ([mcnt], data) = data.split_first_chunk::<1>()?;
-    let (len_bytes, data) = data.split_first_chunk::<2>()?;
+impl StandardHeader {
+    pub fn header_type(&self) -> HeaderType {
+        HeaderType::from_raw(self.htyp)
+    }
+
+    /// Whether the payload is encoded big-endian. The header fields themselves
+    /// are always big-endian.
+    pub fn msb_first(&self) -> bool {
+        self.header_type().msb_first
+    }
+}
+
+/// The flags and version encoded in the `htyp` byte of the standard header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HeaderType {
+    pub use_extended_header: bool,
+    /// Whether the payload is encoded big-endian.
+    pub msb_first: bool,
+    pub with_ecu_id: bool,
+    pub with_session_id: bool,
+    pub with_timestamp: bool,
+    /// Protocol version, 1 for the current spec.
+    pub version: u8,
+}
+
+impl HeaderType {
+    pub fn from_raw(htyp: u8) -> Self {
+        Self {
+            use_extended_header: htyp & 0x01 != 0,
+            msb_first: htyp & 0x02 != 0,
+            with_ecu_id: htyp & 0x04 != 0,
+            with_session_id: htyp & 0x08 != 0,
+            with_timestamp: htyp & 0x10 != 0,
+            version: htyp >> 5,
+        }
+    }
+
+    /// Encodes the flags as an `htyp` byte. Only the low three bits of
+    /// `version` are used.
+    pub fn to_raw(self) -> u8 {
+        u8::from(self.use_extended_header)
+            | u8::from(self.msb_first) << 1
+            | u8::from(self.with_ecu_id) << 2
+            | u8::from(self.with_session_id) << 3
+            | u8::from(self.with_timestamp) << 4
+            | (self.version & 0b111) << 5
+    }
+}
+
+/// Fails with [`DltParseError::UnsupportedVersion`] unless the header
+/// announces protocol version 1, as misframed data would otherwise often be
+/// accepted.
+pub fn parse_standard_header(data: &[u8]) -> Result<(StandardHeader, &[u8]), DltParseError> {
+    parse_standard_header_with(data, &ParseOptions::default())
+}
+
+fn parse_standard_header_with<'a>(
+    data: &'a [u8],
+    opts: &ParseOptions,
+) -> Result<(StandardHeader, &'a [u8]), DltParseError> {
+    let start = data;
 
-    let len = u16::from_be_bytes(*len_bytes) as usize + 16;
+    let ([htyp], data) = take::<1>(start, data)?;
 
-    Some((
+    let version = HeaderType::from_raw(*htyp).version;
+    if opts.strict_version && version != 1 {
+        return Err(DltParseError::UnsupportedVersion { offset: 0, version });
+    }
+
+    let ([mcnt], data) = take::<1>(start, data)?;
+    let (len_bytes, data) = take::<2>(start, data)?;
+
+    let len = u16::from_be_bytes(*len_bytes) as usize;
+
+    Ok((
         StandardHeader {
             htyp: *htyp,
             mcnt: *mcnt,
@@ -117,45 +583,114 @@ pub fn parse_standard_header(data: &[u8]) -> Option<(StandardHeader, &[u8])> {
     ))
 }
 
-pub fn parse_extensions(
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StandardExtensions {
+    pub ecu_id: Option<Id4>,
+    pub session_id: Option<u32>,
+    /// Time since ECU startup in units of 0.1 ms.
+    pub timestamp: Option<u32>,
+}
+
+impl StandardExtensions {
+    /// The ECU ID, or `None` if it is missing or not pure ASCII.
+    pub fn ecu_id_ascii(&self) -> Option<&str> {
+        self.ecu_id.as_ref()?.as_str()
+    }
+
+    /// The timestamp extension converted to the time since ECU startup.
+    pub fn uptime(&self) -> Option<Duration> {
+        self.timestamp
+            .map(|ticks| Duration::from_micros(u64::from(ticks) * 100))
+    }
+}
+
+pub fn parse_extensions<'a>(
     ecu_id: bool,
     session_id: bool,
     timestamp: bool,
-) -> impl Fn(&[u8]) -> Option<((), &[u8])> {
-    return move |data: &[u8]| {
-        let mut bytes = 0;
+) -> impl Fn(&'a [u8]) -> Result<(StandardExtensions, &'a [u8]), DltParseError> {
+    move |data: &'a [u8]| {
+        let start = data;
 
-        if ecu_id {
-            bytes += 4;
-        }
+        let (ecu_id, data) = if ecu_id {
+            let (ecu_bytes, data) = take::<4>(start, data)?;
+            (Some(Id4::new(*ecu_bytes)), data)
+        } else {
+            (None, data)
+        };
 
-        if session_id {
-            bytes += 4;
-        }
+        let (session_id, data) = if session_id {
+            let (session_bytes, data) = take::<4>(start, data)?;
+            (Some(u32::from_be_bytes(*session_bytes)), data)
+        } else {
+            (None, data)
+        };
 
-        if timestamp {
-            bytes += 4;
-        }
+        let (timestamp, data) = if timestamp {
+            let (timestamp_bytes, data) = take::<4>(start, data)?;
+            (Some(u32::from_be_bytes(*timestamp_bytes)), data)
+        } else {
+            (None, data)
+        };
 
-        Some(((), &data[bytes..]))
-    };
+        Ok((
+            StandardExtensions {
+                ecu_id,
+                session_id,
+                timestamp,
+            },
+            data,
+        ))
+    }
 }
 
-#[derive(Debug)]
-pub struct ExtendedHeader<'a> {
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ExtendedHeader {
     pub message_type: MessageInfo,
+    /// Set when the payload is a sequence of self-describing arguments, see
+    /// [`parse_verbose_payload`]. Otherwise it starts with a message ID.
+    pub verbose: bool,
     pub noar: u8,
-    pub apid: Cow<'a, str>,
-    pub ctid: Cow<'a, str>,
+    pub apid: Id4,
+    pub ctid: Id4,
 }
 
-#[derive(Debug)]
+impl ExtendedHeader {
+    /// The application ID, or `None` if it is not pure ASCII.
+    pub fn apid_ascii(&self) -> Option<&str> {
+        self.apid.as_str()
+    }
+
+    /// The context ID, or `None` if it is not pure ASCII.
+    pub fn ctid_ascii(&self) -> Option<&str> {
+        self.ctid.as_str()
+    }
+
+    /// The raw message type (MSTP) field.
+    pub fn raw_type(&self) -> u8 {
+        self.message_type.to_raw().0
+    }
+
+    /// The raw message type info (MTIN) field.
+    pub fn raw_subtype(&self) -> u8 {
+        self.message_type.to_raw().1
+    }
+}
+
+/// Message type and subtype from the extended header.
+///
+/// Types not defined by the spec are kept as `Reserved` with their raw MSTP
+/// and MTIN fields.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum MessageInfo {
     Log { level: LogTypeInfo },
-    AppTrace,
-    NwTrace,
-    Control,
-    Reserved,
+    AppTrace { trace: TraceInfo },
+    NwTrace { network: NetworkTraceType },
+    Control { kind: ControlKind },
+    Reserved { mstp: u8, mtin: u8 },
 }
 
 impl MessageInfo {
@@ -164,15 +699,56 @@ impl MessageInfo {
             0x0 => Self::Log {
                 level: LogTypeInfo::from_raw(data),
             },
-            0x1 => Self::AppTrace,
-            0x2 => Self::NwTrace,
-            0x3 => Self::Control,
-            _ => Self::Reserved,
+            0x1 => Self::AppTrace {
+                trace: TraceInfo::from_raw(data),
+            },
+            0x2 => Self::NwTrace {
+                network: NetworkTraceType::from_raw(data),
+            },
+            0x3 => Self::Control {
+                kind: ControlKind::from_raw(data),
+            },
+            _ => Self::Reserved {
+                mstp: ty,
+                mtin: data,
+            },
         }
     }
+
+    pub(crate) fn to_raw(&self) -> (u8, u8) {
+        match self {
+            Self::Log { level } => (0x0, level.to_raw()),
+            Self::AppTrace { trace } => (0x1, trace.to_raw()),
+            Self::NwTrace { network } => (0x2, network.to_raw()),
+            Self::Control { kind } => (0x3, kind.to_raw()),
+            Self::Reserved { mstp, mtin } => (*mstp, *mtin),
+        }
+    }
+
+    /// The MSIN byte of an extended header with this message type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type does not fit into the 3 bit MSTP and 4 bit MTIN
+    /// fields, as is possible for `Reserved` values.
+    pub(crate) fn to_msin(&self, verbose: bool) -> u8 {
+        let (mstp, mtin) = self.to_raw();
+        assert!(
+            mstp < 0x8 && mtin < 0x10,
+            "message type {mstp}/{mtin} does not fit into the extended header"
+        );
+
+        u8::from(verbose) | (mstp << 1) | (mtin << 4)
+    }
 }
 
-#[derive(Debug)]
+/// Log level of a log message.
+///
+/// Levels are ordered by severity, `Fatal > Error > Warn > Info > Debug >
+/// Verbose`, with the `Reserved` levels below all of them. This is the
+/// opposite of the numeric values used on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum LogTypeInfo {
     Fatal,
     Error,
@@ -180,7 +756,7 @@ pub enum LogTypeInfo {
     Info,
     Debug,
     Verbose,
-    Reserved,
+    Reserved(u8),
 }
 
 impl LogTypeInfo {
@@ -192,7 +768,31 @@ impl LogTypeInfo {
             0x4 => Self::Info,
             0x5 => Self::Debug,
             0x6 => Self::Verbose,
-            _ => Self::Reserved,
+            _ => Self::Reserved(data),
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> u8 {
+        match self {
+            Self::Fatal => 0x1,
+            Self::Error => 0x2,
+            Self::Warn => 0x3,
+            Self::Info => 0x4,
+            Self::Debug => 0x5,
+            Self::Verbose => 0x6,
+            Self::Reserved(data) => data,
+        }
+    }
+
+    pub fn severity(&self) -> u8 {
+        match self {
+            Self::Fatal => 6,
+            Self::Error => 5,
+            Self::Warn => 4,
+            Self::Info => 3,
+            Self::Debug => 2,
+            Self::Verbose => 1,
+            Self::Reserved(_) => 0,
         }
     }
 
@@ -204,26 +804,190 @@ impl LogTypeInfo {
             LogTypeInfo::Info => "info",
             LogTypeInfo::Debug => "debug",
             LogTypeInfo::Verbose => "verbose",
-            LogTypeInfo::Reserved => "reserved",
+            LogTypeInfo::Reserved(_) => "reserved",
+        }
+    }
+}
+
+impl PartialOrd for LogTypeInfo {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LogTypeInfo {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.severity()
+            .cmp(&other.severity())
+            .then(self.to_raw().cmp(&other.to_raw()))
+    }
+}
+
+/// Kind of an application trace message.
+///
+/// Verbose trace payloads use the same argument layout as log messages, see
+/// [`parse_verbose_payload`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TraceInfo {
+    Variable,
+    FunctionIn,
+    FunctionOut,
+    State,
+    Vfb,
+    Reserved(u8),
+}
+
+impl TraceInfo {
+    fn from_raw(data: u8) -> Self {
+        match data {
+            0x1 => Self::Variable,
+            0x2 => Self::FunctionIn,
+            0x3 => Self::FunctionOut,
+            0x4 => Self::State,
+            0x5 => Self::Vfb,
+            _ => Self::Reserved(data),
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> u8 {
+        match self {
+            Self::Variable => 0x1,
+            Self::FunctionIn => 0x2,
+            Self::FunctionOut => 0x3,
+            Self::State => 0x4,
+            Self::Vfb => 0x5,
+            Self::Reserved(data) => data,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            TraceInfo::Variable => "variable",
+            TraceInfo::FunctionIn => "func_in",
+            TraceInfo::FunctionOut => "func_out",
+            TraceInfo::State => "state",
+            TraceInfo::Vfb => "vfb",
+            TraceInfo::Reserved(_) => "reserved",
+        }
+    }
+}
+
+/// Bus or protocol a network trace message was captured from.
+///
+/// Verbose network traces carry the frame as [`Argument::Raw`] arguments,
+/// usually the frame header followed by its payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum NetworkTraceType {
+    Ipc,
+    Can,
+    FlexRay,
+    Most,
+    Ethernet,
+    SomeIp,
+    /// Reserved or user defined.
+    Reserved(u8),
+}
+
+impl NetworkTraceType {
+    fn from_raw(data: u8) -> Self {
+        match data {
+            0x1 => Self::Ipc,
+            0x2 => Self::Can,
+            0x3 => Self::FlexRay,
+            0x4 => Self::Most,
+            0x5 => Self::Ethernet,
+            0x6 => Self::SomeIp,
+            _ => Self::Reserved(data),
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> u8 {
+        match self {
+            Self::Ipc => 0x1,
+            Self::Can => 0x2,
+            Self::FlexRay => 0x3,
+            Self::Most => 0x4,
+            Self::Ethernet => 0x5,
+            Self::SomeIp => 0x6,
+            Self::Reserved(data) => data,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            NetworkTraceType::Ipc => "ipc",
+            NetworkTraceType::Can => "can",
+            NetworkTraceType::FlexRay => "flexray",
+            NetworkTraceType::Most => "most",
+            NetworkTraceType::Ethernet => "ethernet",
+            NetworkTraceType::SomeIp => "someip",
+            NetworkTraceType::Reserved(_) => "reserved",
+        }
+    }
+}
+
+/// Kind of a control message.
+///
+/// Requests are sent to an ECU, which answers with a response carrying the
+/// same service ID, see [`parse_control_payload`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ControlKind {
+    Request,
+    Response,
+    Time,
+    Reserved(u8),
+}
+
+impl ControlKind {
+    fn from_raw(data: u8) -> Self {
+        match data {
+            0x1 => Self::Request,
+            0x2 => Self::Response,
+            0x3 => Self::Time,
+            _ => Self::Reserved(data),
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> u8 {
+        match self {
+            Self::Request => 0x1,
+            Self::Response => 0x2,
+            Self::Time => 0x3,
+            Self::Reserved(data) => data,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            ControlKind::Request => "request",
+            ControlKind::Response => "response",
+            ControlKind::Time => "time",
+            ControlKind::Reserved(_) => "reserved",
         }
     }
 }
 
-pub fn parse_extended_header(data: &[u8]) -> Option<(ExtendedHeader, &[u8])> {
-    let ([msin], data) = data.split_first_chunk::<1>()?;
-    let ([noar], data) = data.split_first_chunk::<1>()?;
-    let (apid_bytes, data) = data.split_first_chunk::<4>()?;
-    let (ctid_bytes, data) = data.split_first_chunk::<4>()?;
+pub fn parse_extended_header(data: &[u8]) -> Result<(ExtendedHeader, &[u8]), DltParseError> {
+    let start = data;
+
+    let ([msin], data) = take::<1>(start, data)?;
+    let ([noar], data) = take::<1>(start, data)?;
+    let (apid_bytes, data) = take::<4>(start, data)?;
+    let (ctid_bytes, data) = take::<4>(start, data)?;
 
-    let apid = String::from_utf8_lossy(strip_null(apid_bytes));
-    let ctid = String::from_utf8_lossy(strip_null(ctid_bytes));
+    let apid = Id4::new(*apid_bytes);
+    let ctid = Id4::new(*ctid_bytes);
 
     let message_type = MessageInfo::from_raw((msin >> 1) & 0b111, (msin >> 4) & 0b1111);
-    let verbose = msin & 0b1;
+    let verbose = msin & 0b1 != 0;
 
-    Some((
+    Ok((
         ExtendedHeader {
             message_type,
+            verbose,
             noar: *noar,
             apid,
             ctid,
@@ -232,12 +996,313 @@ pub fn parse_extended_header(data: &[u8]) -> Option<(ExtendedHeader, &[u8])> {
     ))
 }
 
+fn take<'a, const N: usize>(
+    start: &[u8],
+    data: &'a [u8],
+) -> Result<(&'a [u8; N], &'a [u8]), DltParseError> {
+    data.split_first_chunk::<N>()
+        .ok_or(DltParseError::UnexpectedEof {
+            offset: start.len() - data.len(),
+        })
+}
+
+/// Removes all trailing null bytes, so an all-null slice becomes empty and a
+/// slice without a terminator is kept whole.
 pub fn strip_null(slice: &[u8]) -> &[u8] {
-    for i in (0..slice.len()).rev() {
-        if slice[i] != 0x0 {
-            return &slice[..=i];
+    let len = slice.iter().rposition(|&it| it != 0x0).map_or(0, |i| i + 1);
+    &slice[..len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// A verbose info message from `APP`/`CTX` on `ECU1` with the given
+    /// header type, extensions and payload, behind a storage header.
+    fn frame(htyp: u8, extensions: &[u8], noar: u8, payload: &[u8]) -> Vec<u8> {
+        let len = 4 + extensions.len() + 10 + payload.len();
+
+        let mut data = Vec::new();
+        data.extend_from_slice(b"DLT\x01");
+        data.extend_from_slice(&1_700_000_000u32.to_le_bytes());
+        data.extend_from_slice(&250_000u32.to_le_bytes());
+        data.extend_from_slice(b"ECU1");
+        data.extend_from_slice(&[htyp | 0x01, 7]);
+        data.extend_from_slice(&(len as u16).to_be_bytes());
+        data.extend_from_slice(extensions);
+        data.extend_from_slice(&[0x41, noar]);
+        data.extend_from_slice(b"APP\0CTX\0");
+        data.extend_from_slice(payload);
+        data
+    }
+
+    #[test]
+    fn big_endian_uint32_argument() {
+        // MSBF and version 1, a single UINT32 with type info and value
+        // big-endian.
+        let data = frame(0x22, &[], 1, &[0, 0, 0, 0x43, 0x12, 0x34, 0x56, 0x78]);
+
+        let (message, rest) = parse_message(&data).unwrap();
+        assert!(rest.is_empty());
+        assert!(message.is_big_endian());
+        assert_eq!(message.standard_header.len, 22);
+
+        let arguments = message.arguments().unwrap().unwrap();
+        assert!(matches!(
+            arguments[..],
+            [Argument::UnsignedInt {
+                value: 0x1234_5678,
+                name: None,
+                unit: None
+            }]
+        ));
+    }
+
+    #[test]
+    fn len_beyond_the_buffer_is_unexpected_eof() {
+        let mut data = frame(0x20, &[], 0, &[1, 2, 3, 4]);
+        data[18..20].copy_from_slice(&100u16.to_be_bytes());
+
+        assert!(matches!(
+            parse_message(&data),
+            Err(DltParseError::UnexpectedEof { offset: 34 })
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_to_json() {
+        let data = frame(0x20, &[], 1, &[0x43, 0, 0, 0, 42, 0, 0, 0]);
+        let (message, _) = parse_message(&data).unwrap();
+
+        let json = serde_json::to_value(&message).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "storage_header": {
+                    "pattern": [0x44, 0x4c, 0x54, 0x01],
+                    "timestamp": { "secs": 1_700_000_000, "micros": 250_000 },
+                    "ecu": "ECU1",
+                },
+                "standard_header": { "htyp": 0x21, "mcnt": 7, "len": 22 },
+                "extensions": { "ecu_id": null, "session_id": null, "timestamp": null },
+                "extended_header": {
+                    "message_type": { "Log": { "level": "Info" } },
+                    "verbose": true,
+                    "noar": 1,
+                    "apid": "APP",
+                    "ctid": "CTX",
+                },
+                "payload": [0x43, 0, 0, 0, 42, 0, 0, 0],
+            })
+        );
+    }
+
+    #[test]
+    fn uptime_from_timestamp_ticks() {
+        let data = frame(0x30, &10_000u32.to_be_bytes(), 0, &[]);
+        let (message, _) = parse_message(&data).unwrap();
+
+        assert_eq!(message.extensions.timestamp, Some(10_000));
+        assert_eq!(message.extensions.uptime(), Some(Duration::from_secs(1)));
+
+        let data = frame(0x20, &[], 0, &[]);
+        let (message, _) = parse_message(&data).unwrap();
+        assert_eq!(message.extensions.uptime(), None);
+    }
+
+    #[test]
+    fn extended_header_verbose_flag() {
+        let mut data = frame(0x20, &[], 1, &[0x43, 0, 0, 0, 42, 0, 0, 0]);
+        let (message, _) = parse_message(&data).unwrap();
+        let extended_header = message.extended_header.unwrap();
+        assert!(extended_header.verbose);
+        assert!(matches!(
+            extended_header.message_type,
+            MessageInfo::Log {
+                level: LogTypeInfo::Info
+            }
+        ));
+
+        data[20] &= !0x01;
+        let (message, _) = parse_message(&data).unwrap();
+        assert!(!message.extended_header.unwrap().verbose);
+    }
+
+    fn with_storage_time(mut data: Vec<u8>, secs: u32, micros: u32) -> Vec<u8> {
+        data[4..8].copy_from_slice(&secs.to_le_bytes());
+        data[8..12].copy_from_slice(&micros.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn micros_of_a_second_or_more_are_invalid() {
+        let data = with_storage_time(frame(0x20, &[], 0, &[]), 10, 1_500_000);
+
+        assert!(matches!(
+            parse_message(&data),
+            Err(DltParseError::InvalidMicros {
+                offset: 8,
+                micros: 1_500_000
+            })
+        ));
+    }
+
+    #[test]
+    fn micros_are_checked_or_clamped() {
+        let micros = |data: &[u8], opts: &ParseOptions| {
+            parse_message_with(data, opts)
+                .map(|(message, _)| message.storage_header.unwrap().timestamp.micros)
+        };
+        let strict = ParseOptions::default();
+        let clamping = ParseOptions::new().strict_micros(false);
+
+        for valid in [0, 999_999] {
+            let data = with_storage_time(frame(0x20, &[], 0, &[]), 10, valid);
+            assert_eq!(micros(&data, &strict).unwrap(), valid);
+            assert_eq!(micros(&data, &clamping).unwrap(), valid);
         }
+
+        let data = with_storage_time(frame(0x20, &[], 0, &[]), 10, 2_000_000);
+        assert!(matches!(
+            micros(&data, &strict),
+            Err(DltParseError::InvalidMicros {
+                offset: 8,
+                micros: 2_000_000
+            })
+        ));
+        assert_eq!(micros(&data, &clamping).unwrap(), 999_999);
     }
 
-    slice
+    #[test]
+    fn far_future_secs() {
+        let data = with_storage_time(frame(0x20, &[], 0, &[]), u32::MAX, 999_999);
+        let (message, _) = parse_message(&data).unwrap();
+        let timestamp = message.storage_header.unwrap().timestamp;
+
+        assert_eq!(timestamp.normalized(), (i64::from(u32::MAX), 999_999));
+
+        #[cfg(feature = "chrono")]
+        {
+            let datetime = timestamp.to_datetime().unwrap();
+            assert_eq!(datetime.timestamp(), i64::from(u32::MAX));
+            assert_eq!(datetime.timestamp_subsec_micros(), 999_999);
+        }
+    }
+
+    #[test]
+    fn function_entry_trace() {
+        // A verbose app trace of type function in, with the function name as
+        // a single UTF-8 string argument.
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&(0x200u32 | 1 << 15).to_le_bytes());
+        payload.extend_from_slice(&10u16.to_le_bytes());
+        payload.extend_from_slice(b"init_can\0\0");
+        let mut data = frame(0x20, &[], 1, &payload);
+        data[20] = 0x01 | 1 << 1 | 2 << 4;
+
+        let (message, _) = parse_message(&data).unwrap();
+        let extended_header = message.extended_header.as_ref().unwrap();
+
+        assert!(matches!(
+            extended_header.message_type,
+            MessageInfo::AppTrace {
+                trace: TraceInfo::FunctionIn
+            }
+        ));
+        assert_eq!(extended_header.raw_type(), 1);
+        assert_eq!(extended_header.raw_subtype(), 2);
+        assert_eq!(message.log_level(), None);
+
+        let arguments = message.arguments().unwrap().unwrap();
+        assert_eq!(arguments.len(), 1);
+        assert_eq!(arguments[0].as_str().unwrap(), "init_can");
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let data = frame(0b010 << 5, &[], 0, &[]);
+
+        assert!(matches!(
+            parse_message(&data),
+            Err(DltParseError::UnsupportedVersion {
+                offset: 16,
+                version: 2
+            })
+        ));
+
+        let opts = ParseOptions::new().strict_version(false);
+        let (message, _) = parse_message_with(&data, &opts).unwrap();
+        assert_eq!(message.standard_header.header_type().version, 2);
+    }
+
+    #[test]
+    fn control_message_without_payload() {
+        let mut data = frame(0x20, &[], 0, &[]);
+        // A non-verbose control response.
+        data[20] = 3 << 1 | 2 << 4;
+
+        let (message, rest) = parse_message(&data).unwrap();
+
+        assert!(rest.is_empty());
+        assert_eq!(message.standard_header.len, 14);
+        assert!(message.payload.is_empty());
+        assert!(matches!(
+            message.extended_header.unwrap().message_type,
+            MessageInfo::Control {
+                kind: ControlKind::Response
+            }
+        ));
+        assert!(parse_control_payload(message.payload, false).is_none());
+    }
+
+    #[test]
+    fn len_smaller_than_the_headers_underflows() {
+        let mut data = frame(0x30, &[0; 4], 0, &[]);
+        data[18..20].copy_from_slice(&10u16.to_be_bytes());
+
+        assert!(matches!(
+            parse_message(&data),
+            Err(DltParseError::LengthUnderflow {
+                offset: 16,
+                len: 10,
+                headers: 18
+            })
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn any_len_parses_or_fails_without_panicking(
+            htyp in any::<u8>(),
+            len in any::<u16>(),
+            payload in proptest::collection::vec(any::<u8>(), 0..32),
+        ) {
+            let mut data = frame(htyp & 0x1e | 0x20, &[0; 12], 0, &payload);
+            data[18..20].copy_from_slice(&len.to_be_bytes());
+
+            match parse_message(&data) {
+                Ok((message, rest)) => {
+                    prop_assert_eq!(message.standard_header.len, usize::from(len));
+                    prop_assert_eq!(message.total_len() + rest.len(), data.len());
+                }
+                Err(err) => prop_assert!(err.offset() <= data.len()),
+            }
+        }
+    }
+
+    #[test]
+    fn storage_header_ecu() {
+        let header = |ecu| StorageHeader {
+            pattern: STORAGE_MAGIC,
+            timestamp: StorageTime { secs: 0, micros: 0 },
+            ecu: Id4::new(ecu),
+        };
+
+        assert!(matches!(header(*b"ECU1").ecu(), Cow::Borrowed("ECU1")));
+        assert!(matches!(header(*b"EC\0\0").ecu(), Cow::Borrowed("EC")));
+        assert_eq!(header(*b"E\xffU\0").ecu(), "E\u{fffd}U");
+    }
 }
@@ -0,0 +1,151 @@
+use crate::{messages, Message, MessageIter, StorageTime};
+use alloc::{collections::BinaryHeap, vec::Vec};
+use core::{
+    cmp::{Ordering, Reverse},
+    iter::FusedIterator,
+};
+
+/// Merges the messages of several buffers into one sequence ordered by
+/// storage timestamp.
+///
+/// Each buffer is expected to be in timestamp order already, as captured by a
+/// single logger. Messages with equal timestamps are taken from the earlier
+/// buffer first. Messages that cannot be parsed are skipped.
+pub fn merge<'a>(files: &'a [&'a [u8]]) -> MergedMessages<'a> {
+    let mut iters: Vec<_> = files
+        .iter()
+        .map(|data| messages(data).with_resync(true))
+        .collect();
+
+    let heap = iters
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(source, iter)| Some(Reverse(Entry::new(source, next_ok(iter)?))))
+        .collect();
+
+    MergedMessages { iters, heap }
+}
+
+#[derive(Debug)]
+pub struct MergedMessages<'a> {
+    iters: Vec<MessageIter<'a>>,
+    heap: BinaryHeap<Reverse<Entry<'a>>>,
+}
+
+impl<'a> Iterator for MergedMessages<'a> {
+    type Item = Message<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse(entry) = self.heap.pop()?;
+
+        if let Some(message) = next_ok(&mut self.iters[entry.source]) {
+            self.heap.push(Reverse(Entry::new(entry.source, message)));
+        }
+
+        Some(entry.message)
+    }
+}
+
+impl FusedIterator for MergedMessages<'_> {}
+
+fn next_ok<'a>(iter: &mut MessageIter<'a>) -> Option<Message<'a>> {
+    iter.find_map(Result::ok)
+}
+
+/// The head of one of the merged buffers, ordered by timestamp and then by
+/// the index of the buffer.
+#[derive(Debug)]
+struct Entry<'a> {
+    timestamp: Option<StorageTime>,
+    source: usize,
+    message: Message<'a>,
+}
+
+impl<'a> Entry<'a> {
+    fn new(source: usize, message: Message<'a>) -> Self {
+        Self {
+            timestamp: message.storage_header.as_ref().map(|it| it.timestamp),
+            source,
+            message,
+        }
+    }
+
+    fn key(&self) -> (Option<StorageTime>, usize) {
+        (self.timestamp, self.source)
+    }
+}
+
+impl PartialEq for Entry<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Entry<'_> {}
+
+impl PartialOrd for Entry<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{capture_bytes, info_message};
+
+    /// Messages from `ecu` stored at the given seconds and microseconds.
+    fn capture(ecu: &str, timestamps: &[(u32, u32)]) -> Vec<u8> {
+        capture_bytes(timestamps.iter().enumerate().map(|(i, &(secs, micros))| {
+            info_message(i as u8)
+                .ecu(ecu)
+                .timestamp(StorageTime { secs, micros })
+        }))
+    }
+
+    #[test]
+    fn interleaves_by_timestamp() {
+        let a = capture("ECU1", &[(1, 0), (2, 500), (4, 0), (4, 0)]);
+        let b = capture("ECU2", &[(0, 999_999), (2, 0), (3, 0), (4, 0), (5, 0)]);
+        let files = [&a[..], &b[..]];
+
+        let merged: Vec<_> = merge(&files)
+            .map(|it| {
+                let ecu = it.storage_header.as_ref().unwrap().ecu;
+                (ecu, it.standard_header.mcnt)
+            })
+            .collect();
+        let expected = [
+            ("ECU2", 0),
+            ("ECU1", 0),
+            ("ECU2", 1),
+            ("ECU1", 1),
+            ("ECU2", 2),
+            // Equal timestamps come from the first buffer first.
+            ("ECU1", 2),
+            ("ECU1", 3),
+            ("ECU2", 3),
+            ("ECU2", 4),
+        ];
+
+        assert_eq!(
+            merged,
+            expected.map(|(ecu, mcnt)| (ecu.parse().unwrap(), mcnt))
+        );
+    }
+
+    #[test]
+    fn empty_buffers() {
+        let a = capture("ECU1", &[(1, 0)]);
+        let files = [&[][..], &a[..], &[][..]];
+
+        assert_eq!(merge(&files).count(), 1);
+        assert_eq!(merge(&[]).count(), 0);
+    }
+}
@@ -0,0 +1,19 @@
+use memmap2::Mmap;
+use std::{fs::File, io, path::Path};
+
+/// Maps the file at `path` into memory so it can be parsed without reading it
+/// into a buffer first.
+///
+/// Messages parsed from the map borrow from it.
+///
+/// # Safety
+///
+/// The file must not be truncated or modified, by this or another process,
+/// while the map is alive. Reading a truncated part of the map raises
+/// `SIGBUS`, and modifications change bytes that parsed messages borrow.
+pub unsafe fn open_mmap(path: &Path) -> io::Result<Mmap> {
+    let file = File::open(path)?;
+
+    // SAFETY: upheld by the caller.
+    unsafe { Mmap::map(&file) }
+}
@@ -0,0 +1,42 @@
+use crate::{OwnedMessage, StreamParser};
+use std::{
+    io,
+    net::{SocketAddr, TcpStream},
+};
+
+/// Port a DLT daemon listens on for clients.
+pub const DEFAULT_TCP_PORT: u16 = 3490;
+
+/// Connects to a DLT daemon and streams the messages it sends.
+///
+/// Reconnecting after the connection drops is left to the caller.
+pub fn connect_tcp(addr: SocketAddr) -> io::Result<TcpMessageStream> {
+    let stream = TcpStream::connect(addr)?;
+
+    Ok(TcpMessageStream {
+        parser: StreamParser::without_storage_header(stream),
+    })
+}
+
+/// Messages received from a DLT daemon, see [`connect_tcp`].
+///
+/// Iteration ends when the daemon closes the connection.
+#[derive(Debug)]
+pub struct TcpMessageStream {
+    parser: StreamParser<TcpStream>,
+}
+
+impl TcpMessageStream {
+    /// See [`StreamParser::next_message`].
+    pub fn next_message(&mut self) -> io::Result<Option<OwnedMessage>> {
+        self.parser.next_message()
+    }
+}
+
+impl Iterator for TcpMessageStream {
+    type Item = io::Result<OwnedMessage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_message().transpose()
+    }
+}
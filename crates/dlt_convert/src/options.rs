@@ -0,0 +1,149 @@
+use crate::STORAGE_MAGIC;
+
+/// Settings for [`parse_message_with`](crate::parse_message_with) and
+/// [`messages_with`](crate::messages_with).
+///
+/// The defaults match [`parse_message`](crate::parse_message).
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    pub(crate) storage_magic: [u8; 4],
+    pub(crate) storage_header: bool,
+    pub(crate) strict_version: bool,
+    pub(crate) strict_micros: bool,
+    pub(crate) msb_first: Option<bool>,
+    pub(crate) resync: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            storage_magic: STORAGE_MAGIC,
+            storage_header: true,
+            strict_version: true,
+            strict_micros: true,
+            msb_first: None,
+            resync: false,
+        }
+    }
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expect `magic` at the start of every storage header instead of
+    /// `DLT\x01`, for vendor specific captures.
+    ///
+    /// Only functions taking `ParseOptions` honor this, like the `_with`
+    /// variants of the scanners such as
+    /// [`message_offsets_with`](crate::message_offsets_with) and
+    /// [`filter_file_with`](crate::filter_file_with). The functions without
+    /// options, including `build_index`, `par_messages`, `follow` and
+    /// `StreamParser`, always expect `DLT\x01`.
+    pub fn storage_magic(mut self, magic: [u8; 4]) -> Self {
+        self.storage_magic = magic;
+        self
+    }
+
+    /// Whether messages start with a storage header, as in `.dlt` files.
+    /// Disable for messages as sent over the network.
+    pub fn storage_header(mut self, storage_header: bool) -> Self {
+        self.storage_header = storage_header;
+        self
+    }
+
+    /// Whether to reject standard headers announcing a protocol version other
+    /// than 1.
+    pub fn strict_version(mut self, strict_version: bool) -> Self {
+        self.strict_version = strict_version;
+        self
+    }
+
+    /// Whether to reject storage headers whose microseconds are not below one
+    /// million. When disabled such values are clamped to `999_999`.
+    pub fn strict_micros(mut self, strict_micros: bool) -> Self {
+        self.strict_micros = strict_micros;
+        self
+    }
+
+    /// Treat payloads as big-endian (`true`) or little-endian (`false`)
+    /// regardless of the MSBF flag. The parsed `htyp` keeps the flag as read,
+    /// the override is stored in [`Message::msb_first`](crate::Message::msb_first).
+    pub fn msb_first(mut self, msb_first: bool) -> Self {
+        self.msb_first = Some(msb_first);
+        self
+    }
+
+    /// Whether iteration continues at the next storage header magic after a
+    /// parse error, see [`MessageIter::with_resync`](crate::MessageIter::with_resync).
+    pub fn resync(mut self, resync: bool) -> Self {
+        self.resync = resync;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        filter_file_with, message_len_if_complete_with, message_offsets, message_offsets_with,
+        messages_with, numbered_capture, parse_message_with, payload_slices_with, write_message,
+        Argument, HeaderType, MessageAvail, MessageFilter, STORAGE_HEADER_LEN,
+    };
+    use alloc::vec::Vec;
+
+    #[test]
+    fn vendor_storage_magic() {
+        let mut data = numbered_capture(2);
+        let len = data.len() / 2;
+        for start in [0, len] {
+            data[start..start + 4].copy_from_slice(b"VND\x01");
+        }
+
+        let opts = ParseOptions::new().storage_magic(*b"VND\x01");
+        assert_eq!(messages_with(&data, opts).filter(Result::is_ok).count(), 2);
+        assert!(messages_with(&data, ParseOptions::new())
+            .next()
+            .unwrap()
+            .is_err());
+
+        // Scanners without options only look for the default magic.
+        assert!(message_offsets(&data).is_empty());
+
+        let opts = ParseOptions::new().storage_magic(*b"VND\x01");
+        assert_eq!(message_offsets_with(&data, &opts), [0, len]);
+        assert_eq!(payload_slices_with(&data, &opts).count(), 2);
+        assert_eq!(
+            message_len_if_complete_with(&data, &opts),
+            MessageAvail::Complete(len)
+        );
+
+        let mut out = Vec::new();
+        filter_file_with(&data, &MessageFilter::new(), &opts, &mut out).unwrap();
+        assert_eq!(out, data);
+    }
+
+    #[test]
+    fn msb_first_keeps_the_header_as_read() {
+        // A little-endian payload wrongly flagged as big-endian.
+        let mut data = numbered_capture(1);
+        let htyp = &mut data[STORAGE_HEADER_LEN];
+        let mut flags = HeaderType::from_raw(*htyp);
+        flags.msb_first = true;
+        *htyp = flags.to_raw();
+
+        let opts = ParseOptions::new().msb_first(false);
+        let (message, _) = parse_message_with(&data, &opts).unwrap();
+        assert!(message.standard_header.msb_first());
+        assert!(!message.is_big_endian());
+        assert!(matches!(
+            message.argument_iter().unwrap().next(),
+            Some(Ok(Argument::UnsignedInt { value: 0, .. }))
+        ));
+
+        let mut encoded = Vec::new();
+        write_message(&message, &mut encoded);
+        assert_eq!(encoded, data);
+    }
+}
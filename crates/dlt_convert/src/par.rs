@@ -0,0 +1,72 @@
+use crate::{parse_message, scan::split_messages, DltParseError, OwnedMessage, STORAGE_MAGIC};
+use rayon::prelude::*;
+
+/// Parses all messages of `data` in parallel.
+///
+/// Message boundaries are found up front from the storage headers, then every
+/// message is decoded on the rayon thread pool. The result matches collecting
+/// [`messages`](crate::messages), including stopping after the first error.
+pub fn par_messages(data: &[u8]) -> Vec<Result<OwnedMessage, DltParseError>> {
+    let mut results: Vec<_> = split_messages(data, &STORAGE_MAGIC)
+        .into_par_iter()
+        .map(|(offset, chunk)| {
+            parse_message(chunk)
+                .map(|(message, _)| message.into_owned())
+                .map_err(|err| err.offset_by(offset))
+        })
+        .collect();
+
+    // Framing only stops at the first bad storage header, a message with a
+    // bad standard header may be followed by more that parsed fine.
+    if let Some(first_error) = results.iter().position(Result::is_err) {
+        results.truncate(first_error + 1);
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{corrupt_version, messages, numbered_capture};
+
+    fn assert_same_as_serial(data: &[u8]) {
+        let serial: Vec<_> = messages(data)
+            .map(|it| it.map(|it| it.into_owned()))
+            .collect();
+        let parallel = par_messages(data);
+
+        assert_eq!(format!("{parallel:?}"), format!("{serial:?}"));
+    }
+
+    #[test]
+    fn matches_serial_parsing() {
+        assert_same_as_serial(&[]);
+        assert_same_as_serial(&numbered_capture(100));
+    }
+
+    #[test]
+    fn stops_after_a_bad_standard_header() {
+        let mut data = numbered_capture(3);
+        let second = data.len() / 3;
+        corrupt_version(&mut data, second);
+
+        let results = par_messages(&data);
+        assert_eq!(results.len(), 2);
+        assert!(matches!(
+            results[1],
+            Err(DltParseError::UnsupportedVersion { version: 2, .. })
+        ));
+        assert_same_as_serial(&data);
+    }
+
+    #[test]
+    fn stops_at_garbage() {
+        let mut data = numbered_capture(2);
+        data.extend_from_slice(b"garbage");
+        data.extend_from_slice(&numbered_capture(1));
+
+        assert_eq!(par_messages(&data).len(), 3);
+        assert_same_as_serial(&data);
+    }
+}
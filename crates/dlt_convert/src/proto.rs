@@ -0,0 +1,252 @@
+use crate::{Argument, Message, MessageInfo};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// A message as defined in `proto/dlt.proto`, see [`to_proto`].
+///
+/// The types of the schema are a hand-maintained mirror of the `.proto` file,
+/// written with the `prost` derives so that building the crate needs neither
+/// `prost-build` nor `protoc`. Changes to one have to be made to the other.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct DltMessageProto {
+    /// Missing for messages read without a storage header.
+    #[prost(message, optional, tag = "1")]
+    pub storage_header: Option<StorageHeaderProto>,
+    #[prost(message, optional, tag = "2")]
+    pub standard_header: Option<StandardHeaderProto>,
+    #[prost(message, optional, tag = "3")]
+    pub extensions: Option<ExtensionsProto>,
+    /// Missing for messages without an extended header.
+    #[prost(message, optional, tag = "4")]
+    pub extended_header: Option<ExtendedHeaderProto>,
+    /// The raw payload.
+    #[prost(bytes = "vec", tag = "5")]
+    pub payload: Vec<u8>,
+    /// The decoded arguments of a verbose payload. Empty for non-verbose
+    /// payloads and for verbose ones that fail to decode.
+    #[prost(message, repeated, tag = "6")]
+    pub arguments: Vec<ArgumentProto>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StorageHeaderProto {
+    #[prost(uint32, tag = "1")]
+    pub secs: u32,
+    #[prost(uint32, tag = "2")]
+    pub micros: u32,
+    #[prost(string, tag = "3")]
+    pub ecu: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StandardHeaderProto {
+    #[prost(uint32, tag = "1")]
+    pub htyp: u32,
+    #[prost(uint32, tag = "2")]
+    pub mcnt: u32,
+    /// Length of the message starting at the standard header.
+    #[prost(uint32, tag = "3")]
+    pub len: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExtensionsProto {
+    #[prost(string, optional, tag = "1")]
+    pub ecu_id: Option<String>,
+    #[prost(uint32, optional, tag = "2")]
+    pub session_id: Option<u32>,
+    /// Time since ECU startup in units of 0.1 ms.
+    #[prost(uint32, optional, tag = "3")]
+    pub timestamp: Option<u32>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExtendedHeaderProto {
+    /// The raw MSTP and MTIN fields.
+    #[prost(uint32, tag = "1")]
+    pub mstp: u32,
+    #[prost(uint32, tag = "2")]
+    pub mtin: u32,
+    /// Names of the type and subtype like "log" and "info", or "reserved".
+    #[prost(string, tag = "3")]
+    pub message_type: String,
+    #[prost(string, tag = "4")]
+    pub subtype: String,
+    #[prost(bool, tag = "5")]
+    pub verbose: bool,
+    #[prost(uint32, tag = "6")]
+    pub noar: u32,
+    #[prost(string, tag = "7")]
+    pub apid: String,
+    #[prost(string, tag = "8")]
+    pub ctid: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ArgumentProto {
+    #[prost(string, optional, tag = "1")]
+    pub name: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    pub unit: Option<String>,
+    #[prost(oneof = "argument_proto::Value", tags = "3, 4, 5, 6, 7, 8")]
+    pub value: Option<argument_proto::Value>,
+}
+
+/// Nested types of [`ArgumentProto`].
+pub mod argument_proto {
+    use alloc::{string::String, vec::Vec};
+
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Value {
+        #[prost(bool, tag = "3")]
+        BoolValue(bool),
+        #[prost(sint64, tag = "4")]
+        SignedInt(i64),
+        #[prost(uint64, tag = "5")]
+        UnsignedInt(u64),
+        /// Floats, and fixed point values after scaling.
+        #[prost(double, tag = "6")]
+        Float(f64),
+        /// Strings decoded according to their coding.
+        #[prost(string, tag = "7")]
+        String(String),
+        #[prost(bytes, tag = "8")]
+        Raw(Vec<u8>),
+    }
+}
+
+/// Converts `msg` to its protobuf form, with the headers and the decoded
+/// arguments of its payload.
+pub fn to_proto(msg: &Message) -> DltMessageProto {
+    let arguments = msg
+        .argument_iter()
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>().ok())
+        .unwrap_or_default();
+
+    DltMessageProto {
+        storage_header: msg
+            .storage_header
+            .as_ref()
+            .map(|storage_header| StorageHeaderProto {
+                secs: storage_header.timestamp.secs,
+                micros: storage_header.timestamp.micros,
+                ecu: storage_header.ecu.to_string(),
+            }),
+        standard_header: Some(StandardHeaderProto {
+            htyp: u32::from(msg.standard_header.htyp),
+            mcnt: u32::from(msg.standard_header.mcnt),
+            len: msg.standard_header.len as u32,
+        }),
+        extensions: Some(ExtensionsProto {
+            ecu_id: msg.extensions.ecu_id.map(|it| it.to_string()),
+            session_id: msg.extensions.session_id,
+            timestamp: msg.extensions.timestamp,
+        }),
+        extended_header: msg.extended_header.as_ref().map(|extended_header| {
+            let (message_type, subtype) = match &extended_header.message_type {
+                MessageInfo::Log { level } => ("log", level.as_str()),
+                MessageInfo::AppTrace { trace } => ("app_trace", trace.as_str()),
+                MessageInfo::NwTrace { network } => ("nw_trace", network.as_str()),
+                MessageInfo::Control { kind } => ("control", kind.as_str()),
+                MessageInfo::Reserved { .. } => ("reserved", "reserved"),
+            };
+
+            ExtendedHeaderProto {
+                mstp: u32::from(extended_header.raw_type()),
+                mtin: u32::from(extended_header.raw_subtype()),
+                message_type: message_type.to_string(),
+                subtype: subtype.to_string(),
+                verbose: extended_header.verbose,
+                noar: u32::from(extended_header.noar),
+                apid: extended_header.apid.to_string(),
+                ctid: extended_header.ctid.to_string(),
+            }
+        }),
+        payload: msg.payload.to_vec(),
+        arguments: arguments.iter().map(argument_to_proto).collect(),
+    }
+}
+
+fn argument_to_proto(argument: &Argument) -> ArgumentProto {
+    use argument_proto::Value;
+
+    let value = match argument {
+        Argument::Bool { value, .. } => Value::BoolValue(*value),
+        Argument::SignedInt { value, .. } => Value::SignedInt(*value),
+        Argument::UnsignedInt { value, .. } => Value::UnsignedInt(*value),
+        Argument::Float { value, .. } => Value::Float(*value),
+        Argument::FixedPoint { .. } => Value::Float(argument.scaled_value().unwrap_or_default()),
+        Argument::String { .. } => Value::String(argument.as_str().unwrap_or_default().into()),
+        Argument::Raw { value, .. } => Value::Raw(value.to_vec()),
+    };
+
+    ArgumentProto {
+        name: argument.name().map(String::from),
+        unit: argument.unit().map(String::from),
+        value: Some(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{capture_bytes, parse_message, LogTypeInfo, MessageBuilder, StorageTime};
+    use alloc::vec;
+    use prost::Message as _;
+
+    #[test]
+    fn encode_and_decode_a_message() {
+        let message = MessageBuilder::log(LogTypeInfo::Warn)
+            .ecu("ECU1")
+            .app("APP")
+            .ctx("CTX")
+            .counter(5)
+            .timestamp(StorageTime {
+                secs: 1_700_000_000,
+                micros: 250_000,
+            })
+            .arg_u32(42)
+            .arg_str("hello");
+        let data = capture_bytes([message]);
+        let (message, _) = parse_message(&data).unwrap();
+
+        let proto = to_proto(&message);
+        let decoded = DltMessageProto::decode(proto.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, proto);
+
+        let storage_header = decoded.storage_header.unwrap();
+        assert_eq!(
+            (
+                storage_header.secs,
+                storage_header.micros,
+                storage_header.ecu.as_str()
+            ),
+            (1_700_000_000, 250_000, "ECU1")
+        );
+        assert_eq!(decoded.standard_header.unwrap().mcnt, 5);
+
+        let extended_header = decoded.extended_header.unwrap();
+        assert_eq!(
+            (
+                extended_header.message_type.as_str(),
+                extended_header.subtype.as_str(),
+                extended_header.apid.as_str(),
+                extended_header.ctid.as_str(),
+                extended_header.noar,
+            ),
+            ("log", LogTypeInfo::Warn.as_str(), "APP", "CTX", 2)
+        );
+
+        assert_eq!(decoded.payload, message.payload);
+        let values: Vec<_> = decoded.arguments.into_iter().map(|it| it.value).collect();
+        assert_eq!(
+            values,
+            vec![
+                Some(argument_proto::Value::UnsignedInt(42)),
+                Some(argument_proto::Value::String("hello".into())),
+            ]
+        );
+    }
+}
@@ -0,0 +1,328 @@
+use crate::{
+    parse_extended_header, ExtendedHeader, HeaderType, Id4, ParseOptions, STORAGE_HEADER_LEN,
+};
+use alloc::vec::Vec;
+
+/// Start offsets of the storage framed messages in `data`, found by jumping
+/// from message to message using the storage header length field.
+///
+/// Whenever the storage magic does not match or a length runs past the end of
+/// the buffer, scanning resumes at the next occurrence of the magic.
+pub fn message_offsets(data: &[u8]) -> Vec<usize> {
+    message_offsets_with(data, &ParseOptions::default())
+}
+
+/// Like [`message_offsets`], looking for the storage magic of `opts`. The
+/// other options do not apply to scanning.
+pub fn message_offsets_with(data: &[u8], opts: &ParseOptions) -> Vec<usize> {
+    frames(data, opts.storage_magic)
+        .map(|(offset, _)| offset)
+        .collect()
+}
+
+/// The application ID, context ID and payload of every storage framed message
+/// in `data` that has an extended header.
+///
+/// Only the fields needed to locate the payload are read, the headers are not
+/// validated otherwise. Messages are found like [`message_offsets`] does.
+pub fn payload_slices(data: &[u8]) -> impl Iterator<Item = (Id4, Id4, &[u8])> {
+    payload_slices_with(data, &ParseOptions::default())
+}
+
+/// Like [`payload_slices`], looking for the storage magic of `opts`.
+pub fn payload_slices_with<'a>(
+    data: &'a [u8],
+    opts: &ParseOptions,
+) -> impl Iterator<Item = (Id4, Id4, &'a [u8])> {
+    frames(data, opts.storage_magic).filter_map(|(_, frame)| {
+        let extended = frame.get(STORAGE_HEADER_LEN + extended_header_offset(frame)?..)?;
+
+        let (apid, rest) = extended.get(2..)?.split_first_chunk::<4>()?;
+        let (ctid, payload) = rest.split_first_chunk::<4>()?;
+
+        Some((Id4::new(*apid), Id4::new(*ctid), payload))
+    })
+}
+
+/// The extended header of the storage framed message `frame`, read without
+/// parsing the other headers.
+///
+/// Returns `Some(None)` if the message has no extended header and `None` if
+/// the frame ends before it.
+pub(crate) fn peek_extended_header(frame: &[u8]) -> Option<Option<ExtendedHeader>> {
+    let Some(offset) = extended_header_offset(frame) else {
+        return Some(None);
+    };
+
+    let extended = frame.get(STORAGE_HEADER_LEN + offset..)?;
+    let (extended_header, _) = parse_extended_header(extended).ok()?;
+
+    Some(Some(extended_header))
+}
+
+/// Offset of the extended header from the start of the standard header, or
+/// `None` if the message has none.
+fn extended_header_offset(frame: &[u8]) -> Option<usize> {
+    let header_type = HeaderType::from_raw(*frame.get(STORAGE_HEADER_LEN)?);
+    if !header_type.use_extended_header {
+        return None;
+    }
+
+    let extensions = usize::from(header_type.with_ecu_id)
+        + usize::from(header_type.with_session_id)
+        + usize::from(header_type.with_timestamp);
+
+    Some(4 + 4 * extensions)
+}
+
+/// The offsets and bytes of the messages in `data` framed by storage headers
+/// starting with `magic`.
+fn frames(data: &[u8], magic: [u8; 4]) -> impl Iterator<Item = (usize, &[u8])> {
+    let mut offset = 0;
+
+    core::iter::from_fn(move || {
+        while offset < data.len() {
+            let rest = &data[offset..];
+
+            match message_len(rest, &magic).filter(|&len| len <= rest.len()) {
+                Some(len) => {
+                    let frame = (offset, &rest[..len]);
+                    offset += len;
+                    return Some(frame);
+                }
+                None => match find_magic(&rest[1..], &magic) {
+                    Some(skip) => offset += 1 + skip,
+                    None => break,
+                },
+            }
+        }
+
+        offset = data.len();
+        None
+    })
+}
+
+/// Splits `data` into the byte ranges of consecutive storage framed messages
+/// using only the storage magic and the length field.
+///
+/// Scanning stops at the first position that does not look like a message,
+/// everything from there on is returned as the last chunk so that parsing it
+/// reports the problem.
+#[cfg(feature = "rayon")]
+pub(crate) fn split_messages<'a>(data: &'a [u8], magic: &[u8; 4]) -> Vec<(usize, &'a [u8])> {
+    let mut chunks = Vec::new();
+    let mut offset = 0;
+
+    while offset < data.len() {
+        let rest = &data[offset..];
+
+        let Some(len) = message_len(rest, magic).filter(|&len| len <= rest.len()) else {
+            chunks.push((offset, rest));
+            break;
+        };
+
+        chunks.push((offset, &rest[..len]));
+        offset += len;
+    }
+
+    chunks
+}
+
+/// Offset of the first occurrence of `magic` in `data`. Uses the vectorized
+/// search of `memchr` when the feature is enabled.
+pub(crate) fn find_magic(data: &[u8], magic: &[u8; 4]) -> Option<usize> {
+    #[cfg(feature = "memchr")]
+    {
+        memchr::memmem::find(data, magic)
+    }
+
+    #[cfg(not(feature = "memchr"))]
+    {
+        data.windows(4).position(|it| it == magic)
+    }
+}
+
+/// Whether a buffer starts with a complete message, see
+/// [`message_len_if_complete`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageAvail {
+    /// The message at the start of the buffer is complete and this long.
+    Complete(usize),
+    /// At least this many more bytes are needed. Once the length field has
+    /// arrived this is exactly the number of bytes missing, before that it is
+    /// the number missing up to the end of the length field.
+    NeedMore(usize),
+    /// The buffer does not start with a message.
+    Invalid,
+}
+
+/// Checks whether `data` starts with a complete storage framed message using
+/// only the storage magic and the length field, without parsing it.
+pub fn message_len_if_complete(data: &[u8]) -> MessageAvail {
+    message_len_if_complete_with(data, &ParseOptions::default())
+}
+
+/// Like [`message_len_if_complete`], expecting the storage magic of `opts`.
+pub fn message_len_if_complete_with(data: &[u8], opts: &ParseOptions) -> MessageAvail {
+    let magic = &opts.storage_magic;
+    let magic_len = data.len().min(magic.len());
+    if data[..magic_len] != magic[..magic_len] {
+        return MessageAvail::Invalid;
+    }
+
+    match data.get(STORAGE_HEADER_LEN..) {
+        Some(standard) => standard_len_if_complete(standard).offset_by(STORAGE_HEADER_LEN),
+        None => MessageAvail::NeedMore(STORAGE_HEADER_LEN + 4 - data.len()),
+    }
+}
+
+/// Like [`message_len_if_complete`], for messages that start directly at the
+/// standard header.
+pub fn message_len_if_complete_no_storage(data: &[u8]) -> MessageAvail {
+    standard_len_if_complete(data)
+}
+
+fn standard_len_if_complete(data: &[u8]) -> MessageAvail {
+    let Some(len_bytes) = data.get(2..4) else {
+        return MessageAvail::NeedMore(4 - data.len());
+    };
+
+    match u16::from_be_bytes([len_bytes[0], len_bytes[1]]) as usize {
+        ..4 => MessageAvail::Invalid,
+        len if len > data.len() => MessageAvail::NeedMore(len - data.len()),
+        len => MessageAvail::Complete(len),
+    }
+}
+
+impl MessageAvail {
+    fn offset_by(self, offset: usize) -> Self {
+        match self {
+            Self::Complete(len) => Self::Complete(offset + len),
+            other => other,
+        }
+    }
+}
+
+/// Total length of the storage framed message at the start of `data`,
+/// including the storage header, if it starts with `magic`.
+pub(crate) fn message_len(data: &[u8], magic: &[u8; 4]) -> Option<usize> {
+    if data.get(..4)? != magic {
+        return None;
+    }
+
+    let len = u16::from_be_bytes(*data.get(18..20)?.first_chunk::<2>()?) as usize;
+
+    (len >= 4).then_some(STORAGE_HEADER_LEN + len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{capture_bytes, corrupt_version, info_message, parse_message, DltParseError};
+
+    fn message() -> Vec<u8> {
+        capture_bytes([info_message(0).arg_str("hello")])
+    }
+
+    #[test]
+    fn complete_message() {
+        let data = message();
+        assert_eq!(
+            message_len_if_complete(&data),
+            MessageAvail::Complete(data.len())
+        );
+
+        let mut two = data.clone();
+        two.extend_from_slice(&data);
+        assert_eq!(
+            message_len_if_complete(&two),
+            MessageAvail::Complete(data.len())
+        );
+    }
+
+    #[test]
+    fn cut_mid_header() {
+        let data = message();
+
+        assert_eq!(message_len_if_complete(&[]), MessageAvail::NeedMore(20));
+        assert_eq!(
+            message_len_if_complete(&data[..10]),
+            MessageAvail::NeedMore(10)
+        );
+        assert_eq!(
+            message_len_if_complete(&data[..19]),
+            MessageAvail::NeedMore(1)
+        );
+    }
+
+    #[test]
+    fn cut_mid_payload() {
+        let data = message();
+
+        assert_eq!(
+            message_len_if_complete(&data[..30]),
+            MessageAvail::NeedMore(data.len() - 30)
+        );
+        assert_eq!(
+            message_len_if_complete(&data[..data.len() - 1]),
+            MessageAvail::NeedMore(1)
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let mut data = message();
+        assert_eq!(message_len_if_complete(b"DLX"), MessageAvail::Invalid);
+        assert_eq!(message_len_if_complete(&data[1..]), MessageAvail::Invalid);
+
+        // A length smaller than the standard header itself.
+        data[18..20].copy_from_slice(&3u16.to_be_bytes());
+        assert_eq!(message_len_if_complete(&data), MessageAvail::Invalid);
+    }
+
+    #[test]
+    fn without_storage_header() {
+        let data = message();
+        let standard = &data[STORAGE_HEADER_LEN..];
+
+        assert_eq!(
+            message_len_if_complete_no_storage(standard),
+            MessageAvail::Complete(standard.len())
+        );
+        assert_eq!(
+            message_len_if_complete_no_storage(&standard[..3]),
+            MessageAvail::NeedMore(1)
+        );
+        assert_eq!(
+            message_len_if_complete_no_storage(&standard[..10]),
+            MessageAvail::NeedMore(standard.len() - 10)
+        );
+    }
+
+    #[test]
+    fn parse_at_every_offset() {
+        let mut data = Vec::new();
+        for (i, text) in ["a", "longer text", "", "mid"].into_iter().enumerate() {
+            data.extend(capture_bytes([info_message(i as u8).arg_str(text)]));
+            data.extend_from_slice(&[0x55; 3]);
+        }
+
+        let offsets = message_offsets(&data);
+        assert_eq!(offsets.len(), 4);
+
+        for (i, &offset) in offsets.iter().enumerate() {
+            let (message, rest) = parse_message(&data[offset..]).unwrap();
+            assert_eq!(message.standard_header.mcnt, i as u8);
+            assert_eq!(rest.len(), data.len() - offset - message.total_len());
+            assert!(rest.starts_with(&[0x55; 3]));
+        }
+
+        // Errors are reported relative to the start of the slice.
+        let offset = offsets[1];
+        corrupt_version(&mut data, offset);
+        assert!(matches!(
+            parse_message(&data[offset..]),
+            Err(DltParseError::UnsupportedVersion { offset: 16, .. })
+        ));
+    }
+}
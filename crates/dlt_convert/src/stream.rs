@@ -0,0 +1,281 @@
+use crate::{
+    message_len_if_complete, message_len_if_complete_no_storage, parse_message,
+    parse_message_no_storage, scan::find_magic, MessageAvail, OwnedMessage, STORAGE_HEADER_LEN,
+    STORAGE_MAGIC,
+};
+use std::io::{self, Read};
+
+/// Parses messages from a reader as the bytes arrive.
+///
+/// Incoming bytes are buffered until the length field announces a complete
+/// message, so a message may be split across any number of `read` calls.
+#[derive(Debug)]
+pub struct StreamParser<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    offset: usize,
+    storage_header: bool,
+    /// Set while skipping bytes that do not contain a storage magic.
+    resyncing: bool,
+}
+
+impl<R: Read> StreamParser<R> {
+    /// Parses storage framed messages, as found in `.dlt` files.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+            offset: 0,
+            storage_header: true,
+            resyncing: false,
+        }
+    }
+
+    /// Parses messages that start directly at the standard header, as sent
+    /// by a DLT daemon over the network.
+    pub fn without_storage_header(reader: R) -> Self {
+        Self {
+            storage_header: false,
+            ..Self::new(reader)
+        }
+    }
+
+    /// Reads until the next complete message is buffered and returns it, or
+    /// `None` once the reader is exhausted at a message boundary.
+    ///
+    /// Parse errors are returned as [`io::ErrorKind::InvalidData`] wrapping a
+    /// [`DltParseError`](crate::DltParseError) with an offset relative to the
+    /// start of the stream. The next call continues after the bad message, or
+    /// at the next storage header magic if the message has no usable length,
+    /// reporting a run of bytes without a magic only once.
+    /// Streams without storage headers have nothing to resynchronize on, so
+    /// everything buffered is dropped in that case. A reader that ends in the
+    /// middle of a message yields [`io::ErrorKind::UnexpectedEof`].
+    pub fn next_message(&mut self) -> io::Result<Option<OwnedMessage>> {
+        loop {
+            if let Some(message) = self.take_message()? {
+                return Ok(Some(message));
+            }
+
+            let mut chunk = [0; 4096];
+            let read = match self.reader.read(&mut chunk) {
+                Ok(it) => it,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+
+            if read == 0 {
+                if self.buffer.is_empty() {
+                    return Ok(None);
+                }
+
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "stream ended in the middle of a message",
+                ));
+            }
+
+            self.buffer.extend_from_slice(&chunk[..read]);
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn take_message(&mut self) -> io::Result<Option<OwnedMessage>> {
+        let avail = if self.storage_header {
+            message_len_if_complete(&self.buffer)
+        } else {
+            message_len_if_complete_no_storage(&self.buffer)
+        };
+
+        // Bytes to parse, and to drop if parsing fails.
+        let (len, skip) = match avail {
+            MessageAvail::Complete(len) => {
+                self.resyncing = false;
+                (len, len)
+            }
+            MessageAvail::NeedMore(_) => return Ok(None),
+            MessageAvail::Invalid if self.storage_header => {
+                // Wait for a whole storage header, so that a bad magic is
+                // reported as such.
+                if self.buffer.len() < STORAGE_HEADER_LEN + 4 {
+                    return Ok(None);
+                }
+
+                // The last bytes may be the start of a magic still arriving.
+                let magic = find_magic(&self.buffer[1..], &STORAGE_MAGIC);
+                let skip = magic.map_or(self.buffer.len() - 3, |it| 1 + it);
+
+                // The error for this run of garbage has been returned already.
+                if self.resyncing {
+                    self.resyncing = magic.is_none();
+                    self.buffer.drain(..skip);
+                    self.offset += skip;
+                    return self.take_message();
+                }
+
+                self.resyncing = magic.is_none();
+                (self.buffer.len(), skip)
+            }
+            // Without storage headers there is nothing to resynchronize on.
+            MessageAvail::Invalid => (self.buffer.len(), self.buffer.len()),
+        };
+
+        let data = &self.buffer[..len];
+        let result = if self.storage_header {
+            parse_message(data)
+        } else {
+            parse_message_no_storage(data)
+        };
+
+        match result {
+            Ok((message, _)) => {
+                let message = message.into_owned();
+                self.buffer.drain(..len);
+                self.offset += len;
+                Ok(Some(message))
+            }
+            Err(err) => {
+                let err = err.offset_by(self.offset);
+                self.buffer.drain(..skip);
+                self.offset += skip;
+                Err(io::Error::new(io::ErrorKind::InvalidData, err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{corrupt_version, numbered_capture, DltParseError};
+
+    /// Returns at most `chunk` bytes per `read` call.
+    struct Chunked<'a> {
+        data: &'a [u8],
+        chunk: usize,
+    }
+
+    impl Read for Chunked<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = self.chunk.min(buf.len()).min(self.data.len());
+            buf[..len].copy_from_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            Ok(len)
+        }
+    }
+
+    /// Reads the whole stream, collecting the counters of the messages and
+    /// the kinds of the errors.
+    fn read_all(mut parser: StreamParser<impl Read>) -> Vec<Result<u8, io::ErrorKind>> {
+        let mut results = Vec::new();
+        loop {
+            match parser.next_message() {
+                Ok(Some(message)) => results.push(Ok(message.standard_header.mcnt)),
+                Ok(None) => return results,
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                    results.push(Err(err.kind()));
+                    return results;
+                }
+                Err(err) => results.push(Err(err.kind())),
+            }
+        }
+    }
+
+    #[test]
+    fn continues_after_a_bad_message() {
+        let mut data = numbered_capture(3);
+        let second = data.len() / 3;
+        corrupt_version(&mut data, second);
+
+        let mut parser = StreamParser::new(&data[..]);
+        assert_eq!(
+            parser.next_message().unwrap().unwrap().standard_header.mcnt,
+            0
+        );
+
+        let err = parser.next_message().unwrap_err();
+        let err = err.get_ref().unwrap().downcast_ref::<DltParseError>();
+        assert!(matches!(
+            err,
+            Some(DltParseError::UnsupportedVersion { offset, version: 2 }) if *offset == second + STORAGE_HEADER_LEN
+        ));
+
+        assert_eq!(
+            parser.next_message().unwrap().unwrap().standard_header.mcnt,
+            2
+        );
+        assert!(parser.next_message().unwrap().is_none());
+    }
+
+    #[test]
+    fn resyncs_after_garbage() {
+        let mut data = numbered_capture(1);
+        data.extend_from_slice(&[0xaa; 30]);
+        data.extend_from_slice(&numbered_capture(2));
+
+        assert_eq!(
+            read_all(StreamParser::new(&data[..])),
+            [Ok(0), Err(io::ErrorKind::InvalidData), Ok(0), Ok(1)]
+        );
+    }
+
+    #[test]
+    fn messages_split_across_reads() {
+        let data = numbered_capture(5);
+
+        for chunk in [1, 7] {
+            let parser = StreamParser::new(Chunked { data: &data, chunk });
+            assert_eq!(read_all(parser), [Ok(0), Ok(1), Ok(2), Ok(3), Ok(4)]);
+        }
+    }
+
+    #[test]
+    fn messages_split_across_reads_without_storage_header() {
+        let mut data = Vec::new();
+        let mut rest = &numbered_capture(3)[..];
+        while !rest.is_empty() {
+            let (message, next) = parse_message(rest).unwrap();
+            data.extend_from_slice(&rest[STORAGE_HEADER_LEN..message.total_len()]);
+            rest = next;
+        }
+
+        for chunk in [1, 7] {
+            let parser = StreamParser::without_storage_header(Chunked { data: &data, chunk });
+            assert_eq!(read_all(parser), [Ok(0), Ok(1), Ok(2)]);
+        }
+    }
+
+    #[test]
+    fn recovers_after_an_error_across_reads() {
+        let mut data = numbered_capture(3);
+        let second = data.len() / 3;
+        corrupt_version(&mut data, second);
+        data.extend_from_slice(&[0xaa; 25]);
+        data.extend_from_slice(&numbered_capture(1));
+
+        for chunk in [1, 7] {
+            let parser = StreamParser::new(Chunked { data: &data, chunk });
+            assert_eq!(
+                read_all(parser),
+                [
+                    Ok(0),
+                    Err(io::ErrorKind::InvalidData),
+                    Ok(2),
+                    Err(io::ErrorKind::InvalidData),
+                    Ok(0)
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn ends_mid_message() {
+        let data = numbered_capture(2);
+        let parser = StreamParser::new(&data[..data.len() - 3]);
+
+        assert_eq!(read_all(parser), [Ok(0), Err(io::ErrorKind::UnexpectedEof)]);
+    }
+}
@@ -0,0 +1,242 @@
+use crate::{messages, Id4, LogTypeInfo, MessageFilter, StorageTime};
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "chrono")]
+use core::time::Duration;
+#[cfg(feature = "chrono")]
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+/// Message counts of a whole buffer, see [`summarize`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub total: usize,
+    /// Number of parse errors; scanning resumes at the next storage header
+    /// after each one.
+    pub errors: usize,
+    pub per_ecu: HashMap<Id4, usize>,
+    pub per_app: HashMap<Id4, usize>,
+    pub per_level: HashMap<LogTypeInfo, usize>,
+    /// Earliest storage timestamp.
+    pub min_timestamp: Option<StorageTime>,
+    /// Latest storage timestamp.
+    pub max_timestamp: Option<StorageTime>,
+}
+
+/// Counts the messages of `data` per ECU, application ID and log level in a
+/// single pass.
+pub fn summarize(data: &[u8]) -> Summary {
+    summarize_filtered(data, &MessageFilter::new())
+}
+
+/// Like [`summarize`], counting only the messages that match `filter`.
+///
+/// Parse errors are counted whether or not the message would match.
+pub fn summarize_filtered(data: &[u8], filter: &MessageFilter) -> Summary {
+    let mut summary = Summary::default();
+
+    for message in messages(data).with_resync(true) {
+        let Ok(message) = message else {
+            summary.errors += 1;
+            continue;
+        };
+
+        if !filter.matches(&message) {
+            continue;
+        }
+
+        summary.total += 1;
+
+        if let Some(ecu) = message.ecu() {
+            *summary.per_ecu.entry(ecu).or_default() += 1;
+        }
+
+        if let Some(extended_header) = &message.extended_header {
+            *summary.per_app.entry(extended_header.apid).or_default() += 1;
+        }
+
+        if let Some(level) = message.log_level() {
+            *summary.per_level.entry(level).or_default() += 1;
+        }
+
+        if let Some(storage_header) = &message.storage_header {
+            let timestamp = Some(storage_header.timestamp);
+
+            summary.min_timestamp = summary.min_timestamp.or(timestamp).min(timestamp);
+            summary.max_timestamp = summary.max_timestamp.max(timestamp);
+        }
+    }
+
+    summary
+}
+
+/// Payload bytes logged per application ID in consecutive time buckets of
+/// length `bucket`, aligned to the Unix epoch.
+///
+/// Each bucket is keyed by its start and the buckets of an application are
+/// sorted by time, leaving out empty ones. Messages without a storage or
+/// extended header are not counted. Parse errors are skipped.
+///
+/// # Panics
+///
+/// Panics if `bucket` is shorter than a microsecond.
+#[cfg(feature = "chrono")]
+pub fn throughput_by_app(data: &[u8], bucket: Duration) -> HashMap<Id4, Vec<(DateTime<Utc>, u64)>> {
+    let bucket = i64::try_from(bucket.as_micros()).unwrap_or(i64::MAX);
+    assert!(bucket > 0, "buckets must be at least a microsecond long");
+
+    let mut buckets = HashMap::<Id4, BTreeMap<i64, u64>>::new();
+
+    for message in messages(data).with_resync(true).flatten() {
+        let (Some(storage_header), Some(extended_header)) =
+            (&message.storage_header, &message.extended_header)
+        else {
+            continue;
+        };
+
+        let (secs, micros) = storage_header.timestamp.normalized();
+        let start = (secs * 1_000_000 + i64::from(micros)).div_euclid(bucket) * bucket;
+
+        *buckets
+            .entry(extended_header.apid)
+            .or_default()
+            .entry(start)
+            .or_default() += message.payload.len() as u64;
+    }
+
+    buckets
+        .into_iter()
+        .map(|(apid, buckets)| {
+            let buckets = buckets
+                .into_iter()
+                .filter_map(|(start, bytes)| Some((DateTime::from_timestamp_micros(start)?, bytes)))
+                .collect();
+            (apid, buckets)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{capture_bytes, corrupt_version, MessageBuilder};
+
+    fn capture(messages: &[(&str, &str, LogTypeInfo, u32)]) -> Vec<u8> {
+        capture_bytes(messages.iter().map(|&(ecu, app, level, secs)| {
+            MessageBuilder::log(level)
+                .ecu(ecu)
+                .app(app)
+                .ctx("CTX")
+                .timestamp(StorageTime { secs, micros: 0 })
+                .arg_u32(secs)
+        }))
+    }
+
+    #[test]
+    fn summarize_counts() {
+        let mut data = capture(&[
+            ("ECU1", "APP1", LogTypeInfo::Info, 30),
+            ("ECU1", "APP2", LogTypeInfo::Error, 10),
+            ("ECU2", "APP1", LogTypeInfo::Info, 20),
+            ("ECU1", "APP1", LogTypeInfo::Info, 40),
+        ]);
+        // Break the version of the last message.
+        let start = data.len() / 4 * 3;
+        corrupt_version(&mut data, start);
+
+        let summary = summarize(&data);
+
+        assert_eq!(summary.total, 3);
+        assert_eq!(summary.errors, 1);
+        assert_eq!(
+            summary.per_ecu,
+            HashMap::from([("ECU1".parse().unwrap(), 2), ("ECU2".parse().unwrap(), 1)])
+        );
+        assert_eq!(
+            summary.per_app,
+            HashMap::from([("APP1".parse().unwrap(), 2), ("APP2".parse().unwrap(), 1)])
+        );
+        assert_eq!(
+            summary.per_level,
+            HashMap::from([(LogTypeInfo::Info, 2), (LogTypeInfo::Error, 1)])
+        );
+        assert_eq!(
+            summary.min_timestamp,
+            Some(StorageTime {
+                secs: 10,
+                micros: 0
+            })
+        );
+        assert_eq!(
+            summary.max_timestamp,
+            Some(StorageTime {
+                secs: 30,
+                micros: 0
+            })
+        );
+        assert_eq!(summarize(&[]), Summary::default());
+    }
+
+    #[test]
+    fn summarize_filtered_counts_only_matching_messages() {
+        let data = capture(&[
+            ("ECU1", "APP1", LogTypeInfo::Info, 10),
+            ("ECU1", "APP2", LogTypeInfo::Error, 20),
+            ("ECU2", "APP1", LogTypeInfo::Warn, 30),
+            ("ECU1", "APP1", LogTypeInfo::Debug, 40),
+        ]);
+        let filter = MessageFilter::new()
+            .app_id("APP1".parse().unwrap())
+            .min_level(LogTypeInfo::Warn);
+
+        let summary = summarize_filtered(&data, &filter);
+
+        assert_eq!(summary.total, 1);
+        assert_eq!(summary.errors, 0);
+        assert_eq!(
+            summary.per_ecu,
+            HashMap::from([("ECU2".parse().unwrap(), 1)])
+        );
+        assert_eq!(
+            summary.per_app,
+            HashMap::from([("APP1".parse().unwrap(), 1)])
+        );
+        assert_eq!(summary.per_level, HashMap::from([(LogTypeInfo::Warn, 1)]));
+        assert_eq!(
+            summary.min_timestamp,
+            Some(StorageTime {
+                secs: 30,
+                micros: 0
+            })
+        );
+        assert_eq!(
+            summary.max_timestamp,
+            Some(StorageTime {
+                secs: 30,
+                micros: 0
+            })
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn throughput_of_two_apps_in_two_buckets() {
+        let data = capture(&[
+            ("ECU1", "APP1", LogTypeInfo::Info, 10),
+            ("ECU1", "APP2", LogTypeInfo::Info, 12),
+            ("ECU1", "APP1", LogTypeInfo::Info, 19),
+            ("ECU1", "APP1", LogTypeInfo::Info, 25),
+            ("ECU1", "APP2", LogTypeInfo::Info, 29),
+        ]);
+        let time = |secs| DateTime::from_timestamp(secs, 0).unwrap();
+
+        // Every payload is a single UINT32 argument of 8 bytes.
+        assert_eq!(
+            throughput_by_app(&data, Duration::from_secs(10)),
+            HashMap::from([
+                ("APP1".parse().unwrap(), vec![(time(10), 16), (time(20), 8)]),
+                ("APP2".parse().unwrap(), vec![(time(10), 8), (time(20), 8)]),
+            ])
+        );
+    }
+}
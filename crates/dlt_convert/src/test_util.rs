@@ -0,0 +1,138 @@
+use crate::{
+    builder::parse_id, write_message, HeaderType, LogTypeInfo, MessageBuilder, MessageInfo,
+    STORAGE_HEADER_LEN, STORAGE_MAGIC,
+};
+use alloc::vec::Vec;
+
+/// The 16 bytes of a storage header with the default magic.
+///
+/// # Panics
+///
+/// Panics if `ecu` is longer than 4 bytes or not ASCII.
+pub fn storage_header_bytes(ecu: &str, secs: u32, micros: u32) -> [u8; 16] {
+    let mut bytes = [0; 16];
+    bytes[..4].copy_from_slice(&STORAGE_MAGIC);
+    bytes[4..8].copy_from_slice(&secs.to_le_bytes());
+    bytes[8..12].copy_from_slice(&micros.to_le_bytes());
+    bytes[12..].copy_from_slice(&parse_id(ecu).to_bytes());
+    bytes
+}
+
+/// The 4 bytes of a standard header without its extensions. `len` counts
+/// from the start of the standard header, see
+/// [`StandardHeader::len`](crate::StandardHeader::len).
+///
+/// Header fields are always big-endian, `flags.msb_first` only declares the
+/// byte order of the payload.
+pub fn standard_header_bytes(flags: HeaderType, mcnt: u8, len: u16) -> [u8; 4] {
+    let [len_high, len_low] = len.to_be_bytes();
+    [flags.to_raw(), mcnt, len_high, len_low]
+}
+
+/// The 10 bytes of an extended header.
+///
+/// # Panics
+///
+/// Panics if `apid` or `ctid` is longer than 4 bytes or not ASCII, or if
+/// `message_type` does not fit into the MSIN byte.
+pub fn extended_header_bytes(
+    message_type: &MessageInfo,
+    verbose: bool,
+    noar: u8,
+    apid: &str,
+    ctid: &str,
+) -> [u8; 10] {
+    let mut bytes = [0; 10];
+    bytes[0] = message_type.to_msin(verbose);
+    bytes[1] = noar;
+    bytes[2..6].copy_from_slice(&parse_id(apid).to_bytes());
+    bytes[6..].copy_from_slice(&parse_id(ctid).to_bytes());
+    bytes
+}
+
+/// An info message from `ECU1`, `APP` and `CTX` with the counter `mcnt` and
+/// no arguments yet.
+pub fn info_message(mcnt: u8) -> MessageBuilder {
+    MessageBuilder::log(LogTypeInfo::Info)
+        .ecu("ECU1")
+        .app("APP")
+        .ctx("CTX")
+        .counter(mcnt)
+}
+
+/// A capture of the built `messages`, each behind its storage header.
+pub fn capture_bytes(messages: impl IntoIterator<Item = MessageBuilder>) -> Vec<u8> {
+    let mut data = Vec::new();
+    for message in messages {
+        write_message(&message.build().as_message(), &mut data);
+    }
+    data
+}
+
+/// A capture of `count` [`info_message`]s numbered from 0, each with its
+/// number as a single `u32` argument so that they all have the same length.
+pub fn numbered_capture(count: u8) -> Vec<u8> {
+    capture_bytes((0..count).map(|i| info_message(i).arg_u32(u32::from(i))))
+}
+
+/// Sets the protocol version of the storage framed message at `offset` to 2,
+/// so that parsing it fails with
+/// [`UnsupportedVersion`](crate::DltParseError::UnsupportedVersion).
+///
+/// # Panics
+///
+/// Panics if `data` ends before the standard header of that message.
+pub fn corrupt_version(data: &mut [u8], offset: usize) {
+    let htyp = &mut data[offset + STORAGE_HEADER_LEN];
+    let mut flags = HeaderType::from_raw(*htyp);
+    flags.version = 2;
+    *htyp = flags.to_raw();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_message, write_message, LogTypeInfo, MessageBuilder, StorageTime};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn headers_match_the_encoder() {
+        let flags = HeaderType {
+            use_extended_header: true,
+            msb_first: false,
+            with_ecu_id: false,
+            with_session_id: false,
+            with_timestamp: false,
+            version: 1,
+        };
+        let log = MessageInfo::Log {
+            level: LogTypeInfo::Warn,
+        };
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&storage_header_bytes("ECU1", 10, 20));
+        data.extend_from_slice(&standard_header_bytes(flags, 3, 14));
+        data.extend_from_slice(&extended_header_bytes(&log, true, 0, "APP", "CTX"));
+
+        let (message, rest) = parse_message(&data).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(message.extensions.ecu_id, None);
+
+        let built = MessageBuilder::log(LogTypeInfo::Warn)
+            .ecu("ECU1")
+            .app("APP")
+            .ctx("CTX")
+            .timestamp(StorageTime {
+                secs: 10,
+                micros: 20,
+            })
+            .counter(3)
+            .build();
+        let mut expected = Vec::new();
+        write_message(&built.as_message(), &mut expected);
+
+        // The builder adds the ECU ID extension, which shifts the rest.
+        assert_eq!(data[..16], expected[..16]);
+        assert_eq!(data[20..], expected[24..]);
+    }
+}
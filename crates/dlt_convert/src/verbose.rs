@@ -0,0 +1,952 @@
+use crate::{strip_null, ArgumentError, ArgumentType, Message};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::{
+    fmt::{self, Write},
+    iter::FusedIterator,
+};
+
+const TYLE_MASK: u32 = 0x0000_000f;
+pub(crate) const BOOL: u32 = 0x0000_0010;
+pub(crate) const SINT: u32 = 0x0000_0020;
+pub(crate) const UINT: u32 = 0x0000_0040;
+pub(crate) const FLOA: u32 = 0x0000_0080;
+const ARAY: u32 = 0x0000_0100;
+pub(crate) const STRG: u32 = 0x0000_0200;
+pub(crate) const RAWD: u32 = 0x0000_0400;
+const VARI: u32 = 0x0000_0800;
+const FIXP: u32 = 0x0000_1000;
+const TRAI: u32 = 0x0000_2000;
+const STRU: u32 = 0x0000_4000;
+const SCOD_MASK: u32 = 0x0003_8000;
+pub(crate) const SCOD_SHIFT: u32 = 15;
+
+pub(crate) const SCOD_UTF8: u32 = 0x1;
+
+const EOF: ArgumentError = ArgumentError::UnexpectedEof { offset: 0 };
+
+/// A decoded verbose argument.
+///
+/// `name` and `unit` are only present when the argument carries variable info
+/// (the VARI bit).
+#[derive(Debug)]
+pub enum Argument<'a> {
+    Bool {
+        value: bool,
+        name: Option<Cow<'a, str>>,
+    },
+    SignedInt {
+        value: i64,
+        name: Option<Cow<'a, str>>,
+        unit: Option<Cow<'a, str>>,
+    },
+    UnsignedInt {
+        value: u64,
+        name: Option<Cow<'a, str>>,
+        unit: Option<Cow<'a, str>>,
+    },
+    /// 16, 32 and 64-bit floats, all widened to `f64`. 128-bit floats are not
+    /// supported.
+    Float {
+        value: f64,
+        name: Option<Cow<'a, str>>,
+        unit: Option<Cow<'a, str>>,
+    },
+    /// Integer with the FIXP bit, representing `raw * quantization + offset`,
+    /// see [`Argument::scaled_value`].
+    FixedPoint {
+        raw: i128,
+        quantization: f32,
+        offset: i64,
+        name: Option<Cow<'a, str>>,
+        unit: Option<Cow<'a, str>>,
+    },
+    /// String bytes as stored in the payload, without the null terminator and
+    /// not validated. Use [`Argument::as_str`] to decode them.
+    String {
+        value: &'a [u8],
+        coding: StringCoding,
+        name: Option<Cow<'a, str>>,
+    },
+    Raw {
+        value: &'a [u8],
+        name: Option<Cow<'a, str>>,
+    },
+}
+
+/// String coding (SCOD) of a string argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StringCoding {
+    Ascii,
+    Utf8,
+    Hex,
+    Bin,
+    Reserved(u8),
+}
+
+impl StringCoding {
+    fn from_raw(data: u8) -> Self {
+        match data {
+            0x0 => Self::Ascii,
+            0x1 => Self::Utf8,
+            0x2 => Self::Hex,
+            0x3 => Self::Bin,
+            _ => Self::Reserved(data),
+        }
+    }
+}
+
+impl<'a> Argument<'a> {
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Bool { name, .. }
+            | Self::SignedInt { name, .. }
+            | Self::UnsignedInt { name, .. }
+            | Self::Float { name, .. }
+            | Self::FixedPoint { name, .. }
+            | Self::String { name, .. }
+            | Self::Raw { name, .. } => name.as_deref(),
+        }
+    }
+
+    pub fn unit(&self) -> Option<&str> {
+        match self {
+            Self::SignedInt { unit, .. }
+            | Self::UnsignedInt { unit, .. }
+            | Self::Float { unit, .. }
+            | Self::FixedPoint { unit, .. } => unit.as_deref(),
+            Self::Bool { .. } | Self::String { .. } | Self::Raw { .. } => None,
+        }
+    }
+
+    /// The borrowed bytes of a string or raw argument, or `None` for other
+    /// arguments.
+    pub fn as_bytes(&self) -> Option<&'a [u8]> {
+        match *self {
+            Self::String { value, .. } | Self::Raw { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Decodes a string argument according to its coding, or `None` for other
+    /// arguments.
+    ///
+    /// ASCII strings have any non-ASCII byte replaced and UTF-8 strings are
+    /// decoded lossily, both only allocating if they have to. Hex and binary
+    /// coded strings are written out as hex or binary digits.
+    pub fn as_str(&self) -> Option<Cow<'a, str>> {
+        let Self::String { value, coding, .. } = *self else {
+            return None;
+        };
+
+        Some(match coding {
+            StringCoding::Ascii => decode_ascii(value),
+            StringCoding::Hex => Cow::Owned(digits(value, false)),
+            StringCoding::Bin => Cow::Owned(digits(value, true)),
+            StringCoding::Utf8 | StringCoding::Reserved(_) => String::from_utf8_lossy(value),
+        })
+    }
+
+    /// The physical value of a fixed-point argument, or `None` for other
+    /// arguments.
+    pub fn scaled_value(&self) -> Option<f64> {
+        match *self {
+            Self::FixedPoint {
+                raw,
+                quantization,
+                offset,
+                ..
+            } => Some(raw as f64 * f64::from(quantization) + offset as f64),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! read {
+    ($ty:ty, $data:expr, $msb_first:expr) => {{
+        let (bytes, data) = $data
+            .split_first_chunk::<{ core::mem::size_of::<$ty>() }>()
+            .ok_or(EOF)?;
+        let value = if $msb_first {
+            <$ty>::from_be_bytes(*bytes)
+        } else {
+            <$ty>::from_le_bytes(*bytes)
+        };
+        (value, data)
+    }};
+    ($ty:ty => $into:ty, $data:expr, $msb_first:expr) => {{
+        let (value, data) = read!($ty, $data, $msb_first);
+        (<$into>::from(value), data)
+    }};
+}
+
+impl Message<'_> {
+    /// Decodes the arguments of a verbose message with
+    /// [`parse_verbose_payload`], taking the argument count and byte order from
+    /// the headers. `None` for non-verbose messages.
+    pub fn arguments(&self) -> Option<Result<Vec<Argument<'_>>, ArgumentError>> {
+        let extended_header = self.extended_header.as_ref().filter(|it| it.verbose)?;
+
+        Some(parse_verbose_payload(
+            self.payload,
+            extended_header.noar,
+            self.is_big_endian(),
+        ))
+    }
+
+    /// Lazily decodes the arguments of a verbose message with
+    /// [`verbose_arguments`], taking the argument count and byte order from
+    /// the headers. `None` for non-verbose messages.
+    pub fn argument_iter(&self) -> Option<ArgumentIter<'_>> {
+        let extended_header = self.extended_header.as_ref().filter(|it| it.verbose)?;
+
+        Some(verbose_arguments(
+            self.payload,
+            extended_header.noar,
+            self.is_big_endian(),
+        ))
+    }
+}
+
+/// Decodes all `noar` arguments of a verbose payload.
+///
+/// Unlike [`verbose_arguments`] this also fails with
+/// [`ArgumentError::TrailingBytes`] if the payload continues after the last
+/// argument.
+pub fn parse_verbose_payload(
+    payload: &[u8],
+    noar: u8,
+    msb_first: bool,
+) -> Result<Vec<Argument<'_>>, ArgumentError> {
+    let mut iter = verbose_arguments(payload, noar, msb_first);
+    let arguments = iter.by_ref().collect::<Result<Vec<_>, _>>()?;
+
+    if !iter.remainder().is_empty() {
+        return Err(ArgumentError::TrailingBytes {
+            offset: payload.len() - iter.remainder().len(),
+        });
+    }
+
+    Ok(arguments)
+}
+
+/// Like [`parse_verbose_payload`], but ignores the argument count and decodes
+/// arguments until the payload is exhausted, for loggers that announce the
+/// wrong number of arguments. See [`ArgumentIter::decode_until_end`].
+pub fn parse_verbose_payload_until_end(
+    payload: &[u8],
+    msb_first: bool,
+) -> Result<Vec<Argument<'_>>, ArgumentError> {
+    verbose_arguments(payload, 0, msb_first)
+        .decode_until_end(true)
+        .collect()
+}
+
+/// Lazily decodes the `noar` arguments of a verbose payload.
+///
+/// After the first error the iterator is exhausted.
+pub fn verbose_arguments(payload: &[u8], noar: u8, msb_first: bool) -> ArgumentIter<'_> {
+    ArgumentIter {
+        payload,
+        data: payload,
+        remaining: noar,
+        until_end: false,
+        msb_first,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArgumentIter<'a> {
+    payload: &'a [u8],
+    data: &'a [u8],
+    remaining: u8,
+    until_end: bool,
+    msb_first: bool,
+}
+
+impl<'a> ArgumentIter<'a> {
+    /// When enabled, `noar` is ignored and arguments are decoded until the
+    /// payload is exhausted, for loggers that announce the wrong number of
+    /// arguments.
+    pub fn decode_until_end(mut self, until_end: bool) -> Self {
+        self.until_end = until_end;
+        self
+    }
+
+    /// The part of the payload that has not been decoded yet.
+    pub fn remainder(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl<'a> Iterator for ArgumentIter<'a> {
+    type Item = Result<Argument<'a>, ArgumentError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let done = if self.until_end {
+            self.data.is_empty()
+        } else {
+            self.remaining == 0
+        };
+        if done {
+            return None;
+        }
+
+        match parse_argument(self.data, self.msb_first) {
+            Ok((argument, rest)) => {
+                self.remaining = self.remaining.saturating_sub(1);
+                self.data = rest;
+                Some(Ok(argument))
+            }
+            Err(err) => {
+                self.remaining = 0;
+                self.until_end = false;
+                Some(Err(err.offset_by(self.payload.len() - self.data.len())))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.until_end {
+            // Every argument starts with 4 bytes of type info.
+            return (0, Some(self.data.len() / 4));
+        }
+
+        (0, Some(self.remaining as usize))
+    }
+}
+
+impl FusedIterator for ArgumentIter<'_> {}
+
+/// Errors are returned with offsets relative to the start of the argument.
+fn parse_argument(data: &[u8], msb_first: bool) -> Result<(Argument<'_>, &[u8]), ArgumentError> {
+    let (type_info, data) = read!(u32, data, msb_first);
+
+    parse_value(type_info, data, msb_first)
+}
+
+/// Decodes an argument of a non-verbose payload, which has the layout of a
+/// verbose argument without the type info.
+pub(crate) fn parse_static_argument(
+    ty: ArgumentType,
+    data: &[u8],
+    msb_first: bool,
+) -> Result<(Argument<'_>, &[u8]), ArgumentError> {
+    let type_info = match ty {
+        ArgumentType::Bool => BOOL | 0x1,
+        ArgumentType::I8 => SINT | 0x1,
+        ArgumentType::I16 => SINT | 0x2,
+        ArgumentType::I32 => SINT | 0x3,
+        ArgumentType::I64 => SINT | 0x4,
+        ArgumentType::U8 => UINT | 0x1,
+        ArgumentType::U16 => UINT | 0x2,
+        ArgumentType::U32 => UINT | 0x3,
+        ArgumentType::U64 => UINT | 0x4,
+        ArgumentType::F32 => FLOA | 0x3,
+        ArgumentType::F64 => FLOA | 0x4,
+        ArgumentType::String => STRG | (SCOD_UTF8 << SCOD_SHIFT),
+        ArgumentType::Raw => RAWD,
+    };
+
+    parse_value(type_info, data, msb_first)
+}
+
+/// Decodes the part of an argument following its type info.
+fn parse_value(
+    type_info: u32,
+    data: &[u8],
+    msb_first: bool,
+) -> Result<(Argument<'_>, &[u8]), ArgumentError> {
+    let unsupported = ArgumentError::Unsupported {
+        offset: 0,
+        type_info,
+    };
+
+    // Only integers can be fixed-point.
+    if type_info & (ARAY | TRAI | STRU) != 0
+        || type_info & FIXP != 0 && type_info & (SINT | UINT) == 0
+    {
+        return Err(unsupported);
+    }
+
+    let tyle = type_info & TYLE_MASK;
+    let vari = type_info & VARI != 0;
+
+    if type_info & BOOL != 0 {
+        let (name, data) = read_name(data, vari, msb_first)?;
+        // Booleans may be wider than a byte, any nonzero value is true. An
+        // unset TYLE is read as a single byte.
+        let width = match tyle {
+            0x0 | 0x1 => 1,
+            0x2..=0x5 => 1 << (tyle - 1),
+            _ => return Err(unsupported),
+        };
+        let (value, data) = data.split_at_checked(width).ok_or(EOF)?;
+        let value = value.iter().any(|&it| it != 0);
+        Ok((Argument::Bool { value, name }, data))
+    } else if type_info & SINT != 0 {
+        let (name, unit, data) = read_name_and_unit(data, vari, msb_first)?;
+        let (scaling, data) = read_scaling(type_info, data, msb_first)?;
+        let (value, data) = match tyle {
+            0x1 => read!(i8 => i64, data, msb_first),
+            0x2 => read!(i16 => i64, data, msb_first),
+            0x3 => read!(i32 => i64, data, msb_first),
+            0x4 => read!(i64, data, msb_first),
+            _ => return Err(unsupported),
+        };
+        let argument = match scaling {
+            Some((quantization, offset)) => Argument::FixedPoint {
+                raw: value.into(),
+                quantization,
+                offset,
+                name,
+                unit,
+            },
+            None => Argument::SignedInt { value, name, unit },
+        };
+        Ok((argument, data))
+    } else if type_info & UINT != 0 {
+        let (name, unit, data) = read_name_and_unit(data, vari, msb_first)?;
+        let (scaling, data) = read_scaling(type_info, data, msb_first)?;
+        let (value, data) = match tyle {
+            0x1 => read!(u8 => u64, data, msb_first),
+            0x2 => read!(u16 => u64, data, msb_first),
+            0x3 => read!(u32 => u64, data, msb_first),
+            0x4 => read!(u64, data, msb_first),
+            _ => return Err(unsupported),
+        };
+        let argument = match scaling {
+            Some((quantization, offset)) => Argument::FixedPoint {
+                raw: value.into(),
+                quantization,
+                offset,
+                name,
+                unit,
+            },
+            None => Argument::UnsignedInt { value, name, unit },
+        };
+        Ok((argument, data))
+    } else if type_info & FLOA != 0 {
+        let (name, unit, data) = read_name_and_unit(data, vari, msb_first)?;
+        let (value, data) = match tyle {
+            0x2 => {
+                let (bits, data) = read!(u16, data, msb_first);
+                (f16_to_f64(bits), data)
+            }
+            0x3 => read!(f32 => f64, data, msb_first),
+            0x4 => read!(f64, data, msb_first),
+            _ => return Err(unsupported),
+        };
+        Ok((Argument::Float { value, name, unit }, data))
+    } else if type_info & STRG != 0 {
+        let (len, data) = read!(u16, data, msb_first);
+        let (name, data) = read_name(data, vari, msb_first)?;
+        let (bytes, data) = data.split_at_checked(len as usize).ok_or(EOF)?;
+        let coding = StringCoding::from_raw(((type_info & SCOD_MASK) >> SCOD_SHIFT) as u8);
+
+        // Binary content may end in zeros of its own, so only drop the
+        // terminator.
+        let value = match coding {
+            StringCoding::Hex | StringCoding::Bin => strip_terminator(bytes),
+            _ => strip_null(bytes),
+        };
+
+        Ok((
+            Argument::String {
+                value,
+                coding,
+                name,
+            },
+            data,
+        ))
+    } else if type_info & RAWD != 0 {
+        let (len, data) = read!(u16, data, msb_first);
+        let (name, data) = read_name(data, vari, msb_first)?;
+        let (value, data) = data.split_at_checked(len as usize).ok_or(EOF)?;
+        Ok((Argument::Raw { value, name }, data))
+    } else {
+        Err(unsupported)
+    }
+}
+
+type Name<'a> = Option<Cow<'a, str>>;
+
+fn read_name(data: &[u8], vari: bool, msb_first: bool) -> Result<(Name<'_>, &[u8]), ArgumentError> {
+    if !vari {
+        return Ok((None, data));
+    }
+
+    let (name_len, data) = read!(u16, data, msb_first);
+    let (name, data) = data.split_at_checked(name_len as usize).ok_or(EOF)?;
+
+    Ok((Some(String::from_utf8_lossy(strip_null(name))), data))
+}
+
+fn read_name_and_unit(
+    data: &[u8],
+    vari: bool,
+    msb_first: bool,
+) -> Result<(Name<'_>, Name<'_>, &[u8]), ArgumentError> {
+    if !vari {
+        return Ok((None, None, data));
+    }
+
+    let (name_len, data) = read!(u16, data, msb_first);
+    let (unit_len, data) = read!(u16, data, msb_first);
+    let (name, data) = data.split_at_checked(name_len as usize).ok_or(EOF)?;
+    let (unit, data) = data.split_at_checked(unit_len as usize).ok_or(EOF)?;
+
+    Ok((
+        Some(String::from_utf8_lossy(strip_null(name))),
+        Some(String::from_utf8_lossy(strip_null(unit))),
+        data,
+    ))
+}
+
+type Scaling = Option<(f32, i64)>;
+
+/// Reads the quantization and offset of an integer with the FIXP bit. The
+/// offset has the width of the value, but at least 32 bits.
+fn read_scaling(
+    type_info: u32,
+    data: &[u8],
+    msb_first: bool,
+) -> Result<(Scaling, &[u8]), ArgumentError> {
+    if type_info & FIXP == 0 {
+        return Ok((None, data));
+    }
+
+    let (quantization, data) = read!(f32, data, msb_first);
+    let (offset, data) = match type_info & TYLE_MASK {
+        0x1..=0x3 => read!(i32 => i64, data, msb_first),
+        0x4 => read!(i64, data, msb_first),
+        _ => {
+            return Err(ArgumentError::Unsupported {
+                offset: 0,
+                type_info,
+            })
+        }
+    };
+
+    Ok((Some((quantization, offset)), data))
+}
+
+fn strip_terminator(bytes: &[u8]) -> &[u8] {
+    bytes.strip_suffix(&[0]).unwrap_or(bytes)
+}
+
+fn digits(bytes: &[u8], binary: bool) -> String {
+    let mut text = String::new();
+    write_digits(bytes, binary, &mut text).expect("writing to a String cannot fail");
+    text
+}
+
+/// Writes `bytes` as hex digits, or as binary digits with a space between
+/// bytes.
+fn write_digits(bytes: &[u8], binary: bool, out: &mut impl Write) -> fmt::Result {
+    for (i, byte) in bytes.iter().enumerate() {
+        if binary {
+            if i > 0 {
+                out.write_char(' ')?;
+            }
+            write!(out, "{byte:08b}")?;
+        } else {
+            write!(out, "{byte:02x}")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the text of a string argument like [`Argument::as_str`] returns it,
+/// without allocating.
+pub(crate) fn write_string(
+    value: &[u8],
+    coding: StringCoding,
+    out: &mut impl Write,
+) -> fmt::Result {
+    match coding {
+        StringCoding::Ascii => {
+            for (i, run) in value.split(|it| !it.is_ascii()).enumerate() {
+                if i > 0 {
+                    out.write_char(char::REPLACEMENT_CHARACTER)?;
+                }
+                out.write_str(core::str::from_utf8(run).expect("ASCII is valid UTF-8"))?;
+            }
+            Ok(())
+        }
+        StringCoding::Hex => write_digits(value, false, out),
+        StringCoding::Bin => write_digits(value, true, out),
+        StringCoding::Utf8 | StringCoding::Reserved(_) => write_lossy_utf8(value, out),
+    }
+}
+
+/// Writes `bytes` like [`String::from_utf8_lossy`] decodes them.
+pub(crate) fn write_lossy_utf8(bytes: &[u8], out: &mut impl Write) -> fmt::Result {
+    for chunk in bytes.utf8_chunks() {
+        out.write_str(chunk.valid())?;
+
+        if !chunk.invalid().is_empty() {
+            out.write_char(char::REPLACEMENT_CHARACTER)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn decode_ascii(bytes: &[u8]) -> Cow<'_, str> {
+    if bytes.is_ascii() {
+        return String::from_utf8_lossy(bytes);
+    }
+
+    bytes
+        .iter()
+        .map(|&it| {
+            if it.is_ascii() {
+                char::from(it)
+            } else {
+                char::REPLACEMENT_CHARACTER
+            }
+        })
+        .collect()
+}
+
+/// Converts the bits of an IEEE 754 half precision float.
+fn f16_to_f64(bits: u16) -> f64 {
+    let sign = u32::from(bits & 0x8000) << 16;
+    let exponent = u32::from(bits >> 10) & 0x1f;
+    let mantissa = u32::from(bits & 0x3ff);
+
+    let value = match exponent {
+        0 => {
+            let value = mantissa as f32 / (1 << 24) as f32;
+            if sign != 0 {
+                -value
+            } else {
+                value
+            }
+        }
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13)),
+    };
+
+    f64::from(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A single argument with `type_info` followed by `value`, in the byte
+    /// order selected by `msb_first`.
+    fn argument(type_info: u32, value: &[u8], msb_first: bool) -> Vec<u8> {
+        let mut data = Vec::new();
+        if msb_first {
+            data.extend_from_slice(&type_info.to_be_bytes());
+        } else {
+            data.extend_from_slice(&type_info.to_le_bytes());
+        }
+        data.extend_from_slice(value);
+        data
+    }
+
+    fn float(data: &[u8], msb_first: bool) -> f64 {
+        match parse_verbose_payload(data, 1, msb_first).unwrap()[..] {
+            [Argument::Float { value, .. }] => value,
+            ref other => panic!("not a single float: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn f64_in_both_byte_orders() {
+        // 3.141592653589793
+        let pi = core::f64::consts::PI;
+
+        let le = argument(FLOA | 0x4, &pi.to_le_bytes(), false);
+        let be = argument(FLOA | 0x4, &pi.to_be_bytes(), true);
+
+        assert_eq!(float(&le, false), pi);
+        assert_eq!(float(&be, true), pi);
+        assert_eq!(&le[4..], [0x18, 0x2d, 0x44, 0x54, 0xfb, 0x21, 0x09, 0x40]);
+    }
+
+    #[test]
+    fn f32_is_widened() {
+        let data = argument(FLOA | 0x3, &1.5_f32.to_be_bytes(), true);
+
+        assert_eq!(float(&data, true), 1.5);
+    }
+
+    #[test]
+    fn f16() {
+        let half = |bits: u16| float(&argument(FLOA | 0x2, &bits.to_le_bytes(), false), false);
+
+        assert_eq!(half(0x3c00), 1.0);
+        assert_eq!(half(0x4248), 3.140625);
+        assert_eq!(half(0xc000), -2.0);
+        assert_eq!(half(0x0001), 1.0 / f64::from(1 << 24));
+        assert_eq!(half(0x7c00), f64::INFINITY);
+        assert!(half(0x7e00).is_nan());
+    }
+
+    #[test]
+    fn f128_is_unsupported() {
+        let data = argument(FLOA | 0x5, &[0; 16], false);
+
+        assert_eq!(
+            parse_verbose_payload(&data, 1, false).unwrap_err(),
+            ArgumentError::Unsupported {
+                offset: 0,
+                type_info: FLOA | 0x5
+            }
+        );
+    }
+
+    /// A string argument with the given coding, its length prefix counting the
+    /// null terminator.
+    fn string(coding: u32, text: &[u8]) -> Vec<u8> {
+        let mut value = Vec::new();
+        value.extend_from_slice(&(text.len() as u16 + 1).to_le_bytes());
+        value.extend_from_slice(text);
+        value.push(0);
+        argument(STRG | coding << SCOD_SHIFT, &value, false)
+    }
+
+    #[test]
+    fn ascii_string() {
+        let data = string(0x0, b"hello");
+        let arguments = parse_verbose_payload(&data, 1, false).unwrap();
+
+        assert!(matches!(
+            arguments[..],
+            [Argument::String {
+                value: b"hello",
+                coding: StringCoding::Ascii,
+                name: None
+            }]
+        ));
+        assert_eq!(arguments[0].as_str().unwrap(), "hello");
+    }
+
+    #[test]
+    fn utf8_string_with_multibyte_characters() {
+        let text = "Größe: 5 µm ✓";
+        let data = string(SCOD_UTF8, text.as_bytes());
+        let arguments = parse_verbose_payload(&data, 1, false).unwrap();
+
+        assert_eq!(arguments[0].as_bytes().unwrap(), text.as_bytes());
+        assert!(matches!(arguments[0].as_str(), Some(Cow::Borrowed(it)) if it == text));
+
+        // The same bytes declared as ASCII decode to replacement characters.
+        let data = string(0x0, "µ".as_bytes());
+        let arguments = parse_verbose_payload(&data, 1, false).unwrap();
+        assert_eq!(arguments[0].as_str().unwrap(), "\u{fffd}\u{fffd}");
+    }
+
+    #[test]
+    fn string_longer_than_the_payload() {
+        let mut data = string(0x0, b"hello");
+        data[4..6].copy_from_slice(&100u16.to_le_bytes());
+
+        assert_eq!(
+            parse_verbose_payload(&data, 1, false).unwrap_err(),
+            ArgumentError::UnexpectedEof { offset: 0 }
+        );
+    }
+
+    #[test]
+    fn uint_with_name_and_unit() {
+        let mut value = Vec::new();
+        value.extend_from_slice(&6u16.to_le_bytes());
+        value.extend_from_slice(&3u16.to_le_bytes());
+        value.extend_from_slice(b"speed\0km\0");
+        value.extend_from_slice(&120u32.to_le_bytes());
+        let data = argument(UINT | VARI | 0x3, &value, false);
+
+        let arguments = parse_verbose_payload(&data, 1, false).unwrap();
+
+        assert!(matches!(
+            arguments[..],
+            [Argument::UnsignedInt {
+                value: 120,
+                name: Some(Cow::Borrowed("speed")),
+                unit: Some(Cow::Borrowed("km"))
+            }]
+        ));
+        assert_eq!(arguments[0].name(), Some("speed"));
+        assert_eq!(arguments[0].unit(), Some("km"));
+    }
+
+    fn two_uint32s() -> Vec<u8> {
+        let mut data = argument(UINT | 0x3, &1u32.to_le_bytes(), false);
+        data.extend(argument(UINT | 0x3, &2u32.to_le_bytes(), false));
+        data
+    }
+
+    #[test]
+    fn fewer_arguments_than_noar() {
+        let data = two_uint32s();
+
+        assert_eq!(
+            parse_verbose_payload(&data, 3, false).unwrap_err(),
+            ArgumentError::UnexpectedEof { offset: 16 }
+        );
+        // A truncated value is reported at the start of its argument.
+        assert_eq!(
+            parse_verbose_payload(&data[..14], 2, false).unwrap_err(),
+            ArgumentError::UnexpectedEof { offset: 8 }
+        );
+    }
+
+    #[test]
+    fn bytes_after_noar_arguments() {
+        let data = two_uint32s();
+
+        assert_eq!(
+            parse_verbose_payload(&data, 1, false).unwrap_err(),
+            ArgumentError::TrailingBytes { offset: 8 }
+        );
+        assert_eq!(verbose_arguments(&data, 1, false).count(), 1);
+        assert_eq!(parse_verbose_payload(&data, 2, false).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn fixed_point_with_quantization_and_offset() {
+        let mut value = Vec::new();
+        value.extend_from_slice(&0.5f32.to_le_bytes());
+        value.extend_from_slice(&10i32.to_le_bytes());
+        value.extend_from_slice(&(-7i32).to_le_bytes());
+        let data = argument(SINT | FIXP | 0x3, &value, false);
+
+        let arguments = parse_verbose_payload(&data, 1, false).unwrap();
+
+        assert!(matches!(
+            arguments[..],
+            [Argument::FixedPoint {
+                raw: -7,
+                quantization: 0.5,
+                offset: 10,
+                ..
+            }]
+        ));
+        assert_eq!(arguments[0].scaled_value(), Some(6.5));
+
+        // Unsigned and big-endian, with the offset still 32 bits wide.
+        let mut value = Vec::new();
+        value.extend_from_slice(&0.5f32.to_be_bytes());
+        value.extend_from_slice(&10i32.to_be_bytes());
+        value.extend_from_slice(&7u16.to_be_bytes());
+        let data = argument(UINT | FIXP | 0x2, &value, true);
+
+        let arguments = parse_verbose_payload(&data, 1, true).unwrap();
+        assert_eq!(arguments[0].scaled_value(), Some(13.5));
+    }
+
+    #[test]
+    fn fixed_point_float_is_unsupported() {
+        let data = argument(FLOA | FIXP | 0x3, &[0; 12], false);
+
+        assert!(matches!(
+            parse_verbose_payload(&data, 1, false),
+            Err(ArgumentError::Unsupported { offset: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn hex_coded_string() {
+        // The content ends in a zero byte of its own before the terminator.
+        let data = string(0x2, &[0xde, 0xad, 0x00]);
+        let arguments = parse_verbose_payload(&data, 1, false).unwrap();
+
+        assert!(matches!(
+            arguments[..],
+            [Argument::String {
+                value: &[0xde, 0xad, 0x00],
+                coding: StringCoding::Hex,
+                name: None
+            }]
+        ));
+        assert_eq!(arguments[0].as_str().unwrap(), "dead00");
+        assert_eq!(alloc::format!("{}", arguments[0]), "dead00");
+
+        let data = string(0x3, &[0x05, 0xff]);
+        let arguments = parse_verbose_payload(&data, 1, false).unwrap();
+        assert_eq!(arguments[0].as_str().unwrap(), "00000101 11111111");
+    }
+
+    #[test]
+    fn decode_until_end_ignores_noar() {
+        let data = two_uint32s();
+
+        let arguments: Vec<_> = verbose_arguments(&data, 1, false)
+            .decode_until_end(true)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(arguments.len(), 2);
+        assert!(matches!(
+            arguments[1],
+            Argument::UnsignedInt { value: 2, .. }
+        ));
+
+        assert_eq!(
+            parse_verbose_payload_until_end(&data, false).unwrap().len(),
+            2
+        );
+        assert_eq!(verbose_arguments(&data, 1, false).count(), 1);
+
+        // A truncated last argument is still an error.
+        assert_eq!(
+            parse_verbose_payload_until_end(&data[..14], false).unwrap_err(),
+            ArgumentError::UnexpectedEof { offset: 8 }
+        );
+    }
+
+    #[test]
+    fn one_byte_bool() {
+        let data = argument(BOOL | 0x1, &[0x01], false);
+
+        assert!(matches!(
+            parse_verbose_payload(&data, 1, false).unwrap()[..],
+            [Argument::Bool {
+                value: true,
+                name: None
+            }]
+        ));
+    }
+
+    #[test]
+    fn wide_bool_followed_by_an_argument() {
+        // A 32 bit boolean whose only nonzero byte is not the first one.
+        let mut data = argument(BOOL | 0x3, &[0x00, 0x00, 0x01, 0x00], false);
+        data.extend(argument(UINT | 0x3, &7u32.to_le_bytes(), false));
+
+        assert!(matches!(
+            parse_verbose_payload(&data, 2, false).unwrap()[..],
+            [
+                Argument::Bool { value: true, .. },
+                Argument::UnsignedInt { value: 7, .. }
+            ]
+        ));
+
+        let data = argument(BOOL | 0x2, &[0x00, 0x00], false);
+        assert!(matches!(
+            parse_verbose_payload(&data, 1, false).unwrap()[..],
+            [Argument::Bool { value: false, .. }]
+        ));
+    }
+
+    #[test]
+    fn strings_display_like_as_str() {
+        for coding in 0..5 {
+            let data = string(coding, "hé\u{1}".as_bytes());
+            let data = [&data[..], &string(coding, b"\xff!")].concat();
+
+            for argument in parse_verbose_payload(&data, 2, false).unwrap() {
+                assert_eq!(
+                    alloc::format!("{argument}"),
+                    argument.as_str().unwrap(),
+                    "coding {coding}"
+                );
+            }
+        }
+    }
+}
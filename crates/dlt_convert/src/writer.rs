@@ -0,0 +1,204 @@
+use crate::{
+    messages, write_message, Id4, Message, StandardHeader, StorageHeader, StorageTime,
+    STORAGE_MAGIC,
+};
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{self, Read, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Writes storage framed messages, numbering them with a message counter per
+/// ECU.
+///
+/// Every message gets the next value of its ECU's counter, wrapping after
+/// 255, and by default a storage header stamped with the current time.
+#[derive(Debug)]
+pub struct DltWriter<W> {
+    out: W,
+    counters: HashMap<Id4, u8>,
+    keep_timestamps: bool,
+    buffer: Vec<u8>,
+}
+
+impl DltWriter<File> {
+    /// Opens the `.dlt` file at `path` for appending, creating it if it does
+    /// not exist.
+    ///
+    /// The counters continue from the last message of each ECU already in the
+    /// file. Messages that cannot be parsed are skipped.
+    pub fn append(path: &Path) -> io::Result<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+
+        let mut writer = Self::new(file);
+        for message in messages(&data).with_resync(true).flatten() {
+            writer.counters.insert(
+                message.ecu().unwrap_or_default(),
+                message.standard_header.mcnt,
+            );
+        }
+
+        Ok(writer)
+    }
+}
+
+impl<W: Write> DltWriter<W> {
+    /// Writes to `out` with all counters starting at 0.
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            counters: HashMap::new(),
+            keep_timestamps: false,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// When enabled, messages that already have a storage header keep its
+    /// timestamp instead of getting the current time.
+    pub fn keep_timestamps(mut self, keep: bool) -> Self {
+        self.keep_timestamps = keep;
+        self
+    }
+
+    /// Writes `message` with the next counter value of its ECU.
+    ///
+    /// A message without a storage header gets one with the current time and
+    /// the ECU ID of its extensions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the message does not fit into the 16-bit length field, like
+    /// [`write_message`].
+    pub fn write(&mut self, message: &Message) -> io::Result<()> {
+        let ecu = message.ecu().unwrap_or_default();
+
+        let mcnt = *self
+            .counters
+            .entry(ecu)
+            .and_modify(|it| *it = it.wrapping_add(1))
+            .or_insert(0);
+
+        let storage_header = match &message.storage_header {
+            Some(storage_header) if self.keep_timestamps => storage_header.clone(),
+            Some(storage_header) => StorageHeader {
+                timestamp: now(),
+                ..storage_header.clone()
+            },
+            None => StorageHeader {
+                pattern: STORAGE_MAGIC,
+                timestamp: now(),
+                ecu,
+            },
+        };
+
+        let message = Message {
+            storage_header: Some(storage_header),
+            standard_header: StandardHeader {
+                mcnt,
+                ..message.standard_header.clone()
+            },
+            extensions: message.extensions.clone(),
+            extended_header: message.extended_header.clone(),
+            payload: message.payload,
+            msb_first: message.msb_first,
+        };
+
+        self.buffer.clear();
+        write_message(&message, &mut self.buffer);
+        self.out.write_all(&self.buffer)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+fn now() -> StorageTime {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    StorageTime {
+        secs: since_epoch.as_secs() as u32,
+        micros: since_epoch.subsec_micros(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{info_message, OwnedMessage};
+    use std::{fs, process};
+
+    fn message(ecu: &str, counter: u8) -> OwnedMessage {
+        info_message(counter).ecu(ecu).build()
+    }
+
+    fn counters(path: &Path) -> Vec<(String, u8)> {
+        let data = fs::read(path).unwrap();
+        messages(&data)
+            .map(Result::unwrap)
+            .map(|it| (it.ecu().unwrap().to_string(), it.standard_header.mcnt))
+            .collect()
+    }
+
+    #[test]
+    fn append_continues_the_counters_of_each_ecu() {
+        let path = std::env::temp_dir().join(format!("dlt_convert_{}_append.dlt", process::id()));
+
+        let mut data = Vec::new();
+        for message in [
+            message("ECU1", 253),
+            message("ECU2", 7),
+            message("ECU1", 254),
+        ] {
+            write_message(&message.as_message(), &mut data);
+        }
+        fs::write(&path, data).unwrap();
+
+        let mut writer = DltWriter::append(&path).unwrap();
+        for ecu in ["ECU1", "ECU1", "ECU2", "ECU3"] {
+            writer.write(&message(ecu, 100).as_message()).unwrap();
+        }
+        writer.flush().unwrap();
+        drop(writer);
+
+        let mut writer = DltWriter::append(&path).unwrap();
+        for ecu in ["ECU1", "ECU3"] {
+            writer.write(&message(ecu, 100).as_message()).unwrap();
+        }
+        writer.flush().unwrap();
+        drop(writer);
+
+        let expected = [
+            ("ECU1", 253),
+            ("ECU2", 7),
+            ("ECU1", 254),
+            ("ECU1", 255),
+            ("ECU1", 0),
+            ("ECU2", 8),
+            ("ECU3", 0),
+            ("ECU1", 1),
+            ("ECU3", 1),
+        ];
+        assert_eq!(
+            counters(&path),
+            expected.map(|(ecu, mcnt)| (ecu.to_owned(), mcnt))
+        );
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,55 +1,347 @@
-use clap::Parser;
-use dlt_convert::{parse_message, strip_null, ExtendedHeader, LogTypeInfo, Message, MessageInfo};
-use std::{io::Read, path::PathBuf};
+use clap::{Parser, ValueEnum};
+use dlt_convert::{
+    follow, messages, open_mmap, summarize_filtered, validate, write_csv, ExtendedHeader, Id4,
+    LogTypeInfo, Message, MessageFilter, MessageInfo, Mmap, Summary,
+};
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    io::{self, Write},
+    ops::Deref,
+    path::{Path, PathBuf},
+};
 
 #[derive(Parser, Debug)]
 #[command(version)]
 struct Args {
-    #[arg(short, long)]
+    /// The `.dlt` file to read.
     input: PathBuf,
+
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Only show messages of this application ID.
+    #[arg(long)]
+    app: Option<Id4>,
+
+    /// Only show messages of this context ID.
+    #[arg(long)]
+    ctx: Option<Id4>,
+
+    /// Only show log messages at least this severe.
+    #[arg(long, value_enum)]
+    level: Option<Level>,
+
+    /// Print counts of the matching messages instead of the messages.
+    #[arg(long)]
+    count: bool,
+
+    /// Keep watching the file and print messages as they are appended.
+    #[arg(long, conflicts_with = "count")]
+    follow: bool,
+
+    /// Show timestamps at this offset from UTC, like `+02:00`, in text output.
+    #[arg(long, value_parser = parse_utc_offset, default_value = "+00:00", allow_hyphen_values = true)]
+    utc_offset: i32,
+
+    /// Only check that the whole file parses, failing at the first error.
+    #[arg(long, conflicts_with_all = ["count", "follow"])]
+    check: bool,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    /// One line per log message.
+    Text,
+    /// One JSON object per line and message.
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Level {
+    Fatal,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Verbose,
+}
+
+impl From<Level> for LogTypeInfo {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Fatal => Self::Fatal,
+            Level::Error => Self::Error,
+            Level::Warn => Self::Warn,
+            Level::Info => Self::Info,
+            Level::Debug => Self::Debug,
+            Level::Verbose => Self::Verbose,
+        }
+    }
 }
 
 fn main() {
     let args = Args::parse();
 
-    let mut file = std::fs::File::open(args.input).unwrap();
+    let mut filter = MessageFilter::new();
+    if let Some(app) = args.app {
+        filter = filter.app_id(app);
+    }
+    if let Some(ctx) = args.ctx {
+        filter = filter.ctx_id(ctx);
+    }
+    if let Some(level) = args.level {
+        filter = filter.min_level(level.into());
+    }
 
-    let mut data = Vec::new();
-    file.read_to_end(&mut data).unwrap();
+    if args.follow {
+        if let Format::Csv = args.format {
+            exit_with("--follow does not support csv output");
+        }
 
-    let mut data = data.as_slice();
+        follow_file(&args.input, args.format, &filter, args.utc_offset);
+        return;
+    }
 
-    while !data.is_empty() {
-        let (
-            Message {
-                storage_header,
-                standard_header,
-                extended_header,
-                payload,
-            },
-            rest,
-        ) = parse_message(data).unwrap();
+    let data = match read_input(&args.input) {
+        Ok(it) => it,
+        Err(err) => {
+            eprintln!("error: {}: {err}", args.input.display());
+            std::process::exit(1);
+        }
+    };
 
-        data = rest;
+    if args.check {
+        match validate(&data) {
+            Ok(count) => println!("OK, {count} messages"),
+            Err((offset, err)) => exit_with(format_args!("message at byte {offset}: {err}")),
+        }
+        return;
+    }
 
-        if let Some(ExtendedHeader {
-            message_type: MessageInfo::Log { level },
-            apid,
-            ctid,
-            ..
-        }) = extended_header
-        {
-            let text = String::from_utf8_lossy(strip_null(payload));
-
-            println!(
-                "{} [{:>4}] [{:>4}] [{}] [{}]: {}",
-                storage_header.timestamp.naive_local(),
-                ctid,
+    if args.count {
+        print_summary(&summarize_filtered(&data, &filter));
+        return;
+    }
+
+    let messages = messages(&data)
+        .filter_dlt(&filter)
+        .map(|it| it.unwrap_or_else(|err| exit_with(err)));
+
+    let result = match args.format {
+        Format::Text => {
+            print_text(messages, args.utc_offset);
+            Ok(())
+        }
+        Format::Json => print_json(messages),
+        Format::Csv => write_csv(messages, io::stdout().lock()),
+    };
+
+    if let Err(err) = result {
+        exit_with(err);
+    }
+}
+
+/// Files from this size on are mapped instead of read into memory.
+const MMAP_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+/// The contents of the input file, either read into memory or mapped.
+enum Input {
+    Read(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl Deref for Input {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Read(data) => data,
+            Self::Mapped(map) => map,
+        }
+    }
+}
+
+/// Reads small files into memory and only maps large ones, where copying
+/// would cost the most.
+fn read_input(path: &Path) -> io::Result<Input> {
+    if fs::metadata(path)?.len() < MMAP_THRESHOLD {
+        return fs::read(path).map(Input::Read);
+    }
+
+    // SAFETY: the file must not be truncated while it is mapped. Appending is
+    // fine, and large captures being cut down while read is accepted as rare.
+    unsafe { open_mmap(path) }.map(Input::Mapped)
+}
+
+fn exit_with(err: impl fmt::Display) -> ! {
+    eprintln!("error: {err}");
+    std::process::exit(1);
+}
+
+/// Parses `[+-]HH[:MM]` into seconds east of UTC.
+fn parse_utc_offset(offset: &str) -> Result<i32, String> {
+    let invalid = || String::from("expected an offset like +02:00");
+
+    let (sign, rest) = match offset.split_at_checked(1) {
+        Some(("+", rest)) => (1, rest),
+        Some(("-", rest)) => (-1, rest),
+        _ => return Err(invalid()),
+    };
+    // Digits only, as `parse` would also accept another sign.
+    let number = |digits: &str| {
+        if !digits.bytes().all(|it| it.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        digits.parse::<u8>().map(i32::from).map_err(|_| invalid())
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours = number(hours)?;
+    let minutes = number(minutes)?;
+
+    if hours > 14 || minutes >= 60 {
+        return Err(invalid());
+    }
+
+    Ok(sign * (hours * 3600 + minutes * 60))
+}
+
+fn follow_file(path: &Path, format: Format, filter: &MessageFilter, utc_offset: i32) {
+    let mut text = String::new();
+    let mut out = io::stdout();
+
+    let result = follow(path, |message| {
+        if !filter.matches(&message) {
+            return;
+        }
+
+        match format {
+            Format::Text => print_line(&message, utc_offset, &mut text),
+            Format::Json => {
+                if let Err(err) = write_json(&mut out, &message) {
+                    exit_with(err);
+                }
+            }
+            Format::Csv => unreachable!("rejected before following"),
+        }
+    });
+
+    if let Err(err) = result {
+        exit_with(format_args!("{}: {err}", path.display()));
+    }
+}
+
+fn print_text<'a>(messages: impl Iterator<Item = Message<'a>>, utc_offset: i32) {
+    let mut text = String::new();
+
+    for message in messages {
+        print_line(&message, utc_offset, &mut text);
+    }
+}
+
+fn print_line(message: &Message, utc_offset: i32, text: &mut String) {
+    if let Message {
+        storage_header: Some(storage_header),
+        extended_header:
+            Some(ExtendedHeader {
+                message_type: MessageInfo::Log { level },
                 apid,
-                storage_header.ecu,
-                level.as_str(),
-                text
-            );
+                ctid,
+                ..
+            }),
+        ..
+    } = message
+    {
+        text.clear();
+        message
+            .write_payload_text(text)
+            .expect("writing to a String cannot fail");
+
+        println!(
+            "{} [{:>4}] [{:>4}] [{}] [{}]: {}",
+            storage_header.timestamp.with_offset(utc_offset),
+            ctid,
+            apid,
+            storage_header.ecu,
+            level.as_str(),
+            text
+        );
+    }
+}
+
+fn print_json<'a>(messages: impl Iterator<Item = Message<'a>>) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+
+    for message in messages {
+        write_json(&mut out, &message)?;
+    }
+
+    Ok(())
+}
+
+fn write_json(out: &mut impl Write, message: &Message) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, message)?;
+    out.write_all(b"\n")
+}
+
+fn print_summary(summary: &Summary) {
+    println!("messages: {}", summary.total);
+    println!("errors: {}", summary.errors);
+
+    if let (Some(min), Some(max)) = (summary.min_timestamp, summary.max_timestamp) {
+        println!("first: {min}");
+        println!("last: {max}");
+    }
+
+    print_counts("ecu", &summary.per_ecu);
+    print_counts("app", &summary.per_app);
+
+    let mut levels: Vec<_> = summary.per_level.iter().collect();
+    levels.sort_by(|a, b| b.0.cmp(a.0));
+    for (level, count) in levels {
+        println!("level {}: {count}", level.as_str());
+    }
+}
+
+fn print_counts(label: &str, counts: &HashMap<Id4, usize>) {
+    let mut counts: Vec<_> = counts.iter().collect();
+    counts.sort();
+
+    for (key, count) in counts {
+        println!("{label} {key}: {count}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_checked() {
+        let args =
+            Args::try_parse_from(["dlt-rs", "in.dlt", "--app", "APP1", "--ctx", "CTX"]).unwrap();
+        assert_eq!(args.app, Some("APP1".parse().unwrap()));
+        assert_eq!(args.ctx, Some("CTX".parse().unwrap()));
+
+        for id in ["TOOLONG", "Ä"] {
+            assert!(Args::try_parse_from(["dlt-rs", "in.dlt", "--app", id]).is_err());
+            assert!(Args::try_parse_from(["dlt-rs", "in.dlt", "--ctx", id]).is_err());
+        }
+    }
+
+    #[test]
+    fn utc_offsets() {
+        assert_eq!(parse_utc_offset("+00:00"), Ok(0));
+        assert_eq!(parse_utc_offset("+02"), Ok(7200));
+        assert_eq!(parse_utc_offset("-05:30"), Ok(-19_800));
+        assert_eq!(parse_utc_offset("+14:00"), Ok(50_400));
+
+        for invalid in [
+            "", "02:00", "+", "+-03:00", "--05", "+02:-30", "+02:+30", "+ 2", "+15", "+02:60",
+            "+02:", "+256",
+        ] {
+            assert!(parse_utc_offset(invalid).is_err(), "{invalid:?}");
         }
     }
 }